@@ -0,0 +1,341 @@
+//! The state of the mapped actions and axes
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        gamepad::{GamepadAxis, GamepadButton},
+        mouse::{MouseMotion, MouseWheel},
+    },
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{AxisBinding, InputBinding, InputMap, MouseAxis};
+
+/// Resource with the state of the actions and axes in the [`InputMap`], updated every frame in
+/// [`PreUpdate`]
+///
+/// Actions and axes that aren't in the [`InputMap`] are never pressed and always have a value of
+/// `0.0`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_input::prelude::*;
+/// # #[derive(Component)]
+/// # struct Player;
+/// fn move_player(actions: Res<ActionState>, mut players: Query<&mut Transform, With<Player>>) {
+///     let movement = actions.axis_pair("move_x", "move_y").clamp_length_max(1.0);
+///     for mut transform in &mut players {
+///         transform.translation += movement.extend(0.0);
+///
+///         if actions.just_pressed("jump") {
+///             transform.translation.y += 10.0;
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ActionState {
+    pressed: HashSet<String>,
+    just_pressed: HashSet<String>,
+    just_released: HashSet<String>,
+    axes: HashMap<String, f32>,
+}
+
+impl ActionState {
+    /// Whether or not any of the bindings of an action are pressed
+    pub fn pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    /// Whether or not an action started being pressed this frame
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.just_pressed.contains(action)
+    }
+
+    /// Whether or not an action stopped being pressed this frame
+    pub fn just_released(&self, action: &str) -> bool {
+        self.just_released.contains(action)
+    }
+
+    /// Iterate over the actions that are pressed
+    pub fn get_pressed(&self) -> impl Iterator<Item = &str> {
+        self.pressed.iter().map(String::as_str)
+    }
+
+    /// Get the value of an axis
+    ///
+    /// When more than one of the axis' bindings are active, the value furthest from zero is used.
+    /// Button and gamepad values range from `-1.0` to `1.0`, but mouse values are not limited.
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axes.get(axis).copied().unwrap_or_default()
+    }
+
+    /// Get the values of two axes as a vector
+    ///
+    /// Holding two button pairs at once gives a diagonal that is longer than `1.0`, so use
+    /// [`Vec2::clamp_length_max`] for movement that should be the same speed in every direction.
+    pub fn axis_pair(&self, x_axis: &str, y_axis: &str) -> Vec2 {
+        Vec2::new(self.axis(x_axis), self.axis(y_axis))
+    }
+}
+
+/// The input resources and events that bindings are read from
+#[derive(SystemParam)]
+pub(crate) struct InputSources<'w, 's> {
+    keyboard: Res<'w, Input<KeyCode>>,
+    mouse_buttons: Res<'w, Input<MouseButton>>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+    gamepad_axes: Res<'w, Axis<GamepadAxis>>,
+    gamepads: Res<'w, Gamepads>,
+    mouse_motion: EventReader<'w, 's, MouseMotion>,
+    mouse_wheel: EventReader<'w, 's, MouseWheel>,
+}
+
+impl<'w, 's> InputSources<'w, 's> {
+    /// The gamepads that are read, which is either the one selected in the input map or all of the
+    /// connected ones
+    fn gamepads<'a>(&'a self, selected: Option<Gamepad>) -> impl Iterator<Item = Gamepad> + 'a {
+        self.gamepads
+            .iter()
+            .filter(move |gamepad| selected.map(|x| x == *gamepad).unwrap_or(true))
+    }
+
+    fn pressed(&self, binding: InputBinding, selected_gamepad: Option<Gamepad>) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keyboard.pressed(key),
+            InputBinding::Mouse(button) => self.mouse_buttons.pressed(button),
+            InputBinding::GamepadButton(button) => self.gamepads(selected_gamepad).any(|gamepad| {
+                self.gamepad_buttons
+                    .pressed(GamepadButton::new(gamepad, button))
+            }),
+        }
+    }
+
+    fn just_pressed(&self, binding: InputBinding, selected_gamepad: Option<Gamepad>) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.keyboard.just_pressed(key),
+            InputBinding::Mouse(button) => self.mouse_buttons.just_pressed(button),
+            InputBinding::GamepadButton(button) => self.gamepads(selected_gamepad).any(|gamepad| {
+                self.gamepad_buttons
+                    .just_pressed(GamepadButton::new(gamepad, button))
+            }),
+        }
+    }
+
+    /// Get the first binding that was pressed this frame, if any
+    pub(crate) fn any_just_pressed(
+        &self,
+        selected_gamepad: Option<Gamepad>,
+    ) -> Option<InputBinding> {
+        if let Some(key) = self.keyboard.get_just_pressed().next() {
+            return Some(InputBinding::Key(*key));
+        }
+        if let Some(button) = self.mouse_buttons.get_just_pressed().next() {
+            return Some(InputBinding::Mouse(*button));
+        }
+        self.gamepad_buttons
+            .get_just_pressed()
+            .find(|button| {
+                selected_gamepad
+                    .map(|x| x == button.gamepad)
+                    .unwrap_or(true)
+            })
+            .map(|button| InputBinding::GamepadButton(button.button_type))
+    }
+}
+
+/// System that updates the [`ActionState`] from the [`InputMap`]
+pub(crate) fn update_action_state(
+    input_map: Res<InputMap>,
+    mut action_state: ResMut<ActionState>,
+    mut sources: InputSources,
+) {
+    let selected_gamepad = input_map.gamepad;
+    let action_state = &mut *action_state;
+
+    let mut pressed = HashSet::default();
+    action_state.just_pressed.clear();
+    for (action, bindings) in &input_map.actions {
+        if !bindings
+            .iter()
+            .any(|binding| sources.pressed(*binding, selected_gamepad))
+        {
+            continue;
+        }
+
+        // Actions that were just bound to a button that is already held don't count as just
+        // pressed, so that the button pressed while rebinding doesn't trigger the action
+        if !action_state.pressed.contains(action)
+            && bindings
+                .iter()
+                .any(|binding| sources.just_pressed(*binding, selected_gamepad))
+        {
+            action_state.just_pressed.insert(action.clone());
+        }
+        pressed.insert(action.clone());
+    }
+    action_state.just_released = action_state.pressed.difference(&pressed).cloned().collect();
+    action_state.pressed = pressed;
+
+    let mut mouse_motion = Vec2::ZERO;
+    for event in sources.mouse_motion.iter() {
+        mouse_motion += event.delta;
+    }
+    mouse_motion *= input_map.mouse_sensitivity;
+    let mut mouse_wheel = Vec2::ZERO;
+    for event in sources.mouse_wheel.iter() {
+        mouse_wheel += Vec2::new(event.x, event.y);
+    }
+    let mouse_value = |value: Vec2, axis: MouseAxis| match axis {
+        MouseAxis::X => value.x,
+        MouseAxis::Y => value.y,
+    };
+
+    action_state.axes.clear();
+    for (axis, bindings) in &input_map.axes {
+        let mut axis_value = 0.0f32;
+        for binding in bindings {
+            let value = match *binding {
+                AxisBinding::Buttons { negative, positive } => {
+                    let button_value = |binding| {
+                        if sources.pressed(binding, selected_gamepad) {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    };
+                    button_value(positive) - button_value(negative)
+                }
+                AxisBinding::GamepadAxis(axis_type) => sources
+                    .gamepads(selected_gamepad)
+                    .filter_map(|gamepad| {
+                        sources
+                            .gamepad_axes
+                            .get(GamepadAxis::new(gamepad, axis_type))
+                    })
+                    .map(|value| apply_dead_zone(value, input_map.dead_zone))
+                    .fold(0.0, |a: f32, b: f32| if b.abs() > a.abs() { b } else { a }),
+                AxisBinding::MouseMotion(axis) => mouse_value(mouse_motion, axis),
+                AxisBinding::MouseWheel(axis) => mouse_value(mouse_wheel, axis),
+            };
+
+            if value.abs() > axis_value.abs() {
+                axis_value = value;
+            }
+        }
+        action_state.axes.insert(axis.clone(), axis_value);
+    }
+}
+
+/// Zero values inside of the dead zone, and rescale the rest so that they still start from zero
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    if value.abs() <= dead_zone {
+        0.0
+    } else {
+        value.signum() * ((value.abs() - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::{keyboard::KeyboardInput, ButtonState, InputPlugin};
+
+    use super::*;
+    use crate::{InputRebinding, RebindFinished, RebindTarget, RetroInputPlugin};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((InputPlugin, RetroInputPlugin));
+        app
+    }
+
+    fn send_key(app: &mut App, key_code: KeyCode, state: ButtonState) {
+        app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    #[test]
+    fn dead_zone() {
+        assert_eq!(apply_dead_zone(0.1, 0.15), 0.0);
+        assert_eq!(apply_dead_zone(-0.15, 0.15), 0.0);
+        assert_eq!(apply_dead_zone(1.0, 0.15), 1.0);
+        assert_eq!(apply_dead_zone(-1.0, 0.15), -1.0);
+        assert!((apply_dead_zone(0.575, 0.15) - 0.5).abs() < 1e-6);
+        assert_eq!(apply_dead_zone(0.5, 0.0), 0.5);
+
+        // The dead zone is clamped so that there is always some range left
+        assert_eq!(apply_dead_zone(0.99, 2.0), 0.0);
+        assert_eq!(apply_dead_zone(1.0, 2.0), 1.0);
+        assert!((apply_dead_zone(0.995, 1.0) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn just_pressed_and_released() {
+        let mut app = app();
+        app.world
+            .resource_mut::<InputMap>()
+            .bind_action("jump", KeyCode::Space)
+            .bind_axis(
+                "move_x",
+                AxisBinding::buttons(KeyCode::Left, KeyCode::Right),
+            );
+
+        send_key(&mut app, KeyCode::Space, ButtonState::Pressed);
+        send_key(&mut app, KeyCode::Left, ButtonState::Pressed);
+        app.update();
+        let actions = app.world.resource::<ActionState>();
+        assert!(actions.pressed("jump"));
+        assert!(actions.just_pressed("jump"));
+        assert!(!actions.just_released("jump"));
+        assert_eq!(actions.axis("move_x"), -1.0);
+        assert_eq!(actions.axis("unbound"), 0.0);
+
+        app.update();
+        let actions = app.world.resource::<ActionState>();
+        assert!(actions.pressed("jump"));
+        assert!(!actions.just_pressed("jump"));
+
+        send_key(&mut app, KeyCode::Space, ButtonState::Released);
+        app.update();
+        let actions = app.world.resource::<ActionState>();
+        assert!(!actions.pressed("jump"));
+        assert!(actions.just_released("jump"));
+
+        app.update();
+        assert!(!app.world.resource::<ActionState>().just_released("jump"));
+    }
+
+    #[test]
+    fn button_held_while_rebinding_is_not_just_pressed() {
+        let mut app = app();
+        app.world
+            .resource_mut::<InputRebinding>()
+            .start(RebindTarget::Action("jump".into()));
+
+        send_key(&mut app, KeyCode::Space, ButtonState::Pressed);
+        app.update();
+        assert_eq!(
+            app.world.resource::<InputMap>().action_bindings("jump"),
+            [InputBinding::Key(KeyCode::Space)]
+        );
+        let events = app.world.resource::<Events<RebindFinished>>();
+        let event = events.iter_current_update_events().next().unwrap();
+        assert_eq!(event.binding, Some(InputBinding::Key(KeyCode::Space)));
+        assert!(!app.world.resource::<InputRebinding>().is_active());
+
+        for _ in 0..2 {
+            app.update();
+            let actions = app.world.resource::<ActionState>();
+            assert!(actions.pressed("jump"));
+            assert!(!actions.just_pressed("jump"));
+        }
+    }
+}
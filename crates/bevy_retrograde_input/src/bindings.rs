@@ -0,0 +1,265 @@
+//! Input bindings and the input map
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    input::gamepad::{GamepadAxisType, GamepadButtonType},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+/// A button that can be bound to an action
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    /// A keyboard key
+    Key(KeyCode),
+    /// A mouse button
+    Mouse(MouseButton),
+    /// A gamepad button
+    GamepadButton(GamepadButtonType),
+}
+
+impl From<KeyCode> for InputBinding {
+    fn from(key: KeyCode) -> Self {
+        InputBinding::Key(key)
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(button: MouseButton) -> Self {
+        InputBinding::Mouse(button)
+    }
+}
+
+impl From<GamepadButtonType> for InputBinding {
+    fn from(button: GamepadButtonType) -> Self {
+        InputBinding::GamepadButton(button)
+    }
+}
+
+/// An input that can be bound to an axis, which has a value from `-1.0` to `1.0`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum AxisBinding {
+    /// A pair of buttons, where the axis is `-1.0` while the negative button is pressed and `1.0`
+    /// while the positive button is pressed
+    Buttons {
+        negative: InputBinding,
+        positive: InputBinding,
+    },
+    /// A gamepad stick or trigger axis
+    GamepadAxis(GamepadAxisType),
+    /// The movement of the mouse this frame, in logical pixels multiplied by the
+    /// [`InputMap::mouse_sensitivity`]. Positive `y` is down.
+    MouseMotion(MouseAxis),
+    /// The scrolling of the mouse wheel this frame, in lines
+    MouseWheel(MouseAxis),
+}
+
+impl AxisBinding {
+    /// Create a binding for a pair of buttons
+    pub fn buttons(negative: impl Into<InputBinding>, positive: impl Into<InputBinding>) -> Self {
+        AxisBinding::Buttons {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+}
+
+/// An axis of mouse motion or scrolling
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseAxis {
+    X,
+    Y,
+}
+
+/// Resource that maps named actions and axes to the inputs that control them
+///
+/// The input map can be serialized, so that players' bindings can be saved. With the Bevy
+/// Retrograde `save` feature it can be persisted by registering it as a save resource with
+/// `app.register_save_resource::<InputMap>("input_map")`. The selected
+/// [`gamepad`][Self::gamepad] isn't saved, because gamepad ids change between runs.
+///
+/// Gamepads are only read if Bevy's `bevy_gilrs` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::{input::gamepad::{GamepadAxisType, GamepadButtonType}, prelude::*};
+/// # use bevy_retrograde_input::prelude::*;
+/// fn setup(mut input_map: ResMut<InputMap>) {
+///     input_map
+///         .bind_action("jump", KeyCode::Space)
+///         .bind_action("jump", GamepadButtonType::South)
+///         .bind_axis("move_x", AxisBinding::buttons(KeyCode::Left, KeyCode::Right))
+///         .bind_axis("move_x", AxisBinding::GamepadAxis(GamepadAxisType::LeftStickX));
+/// }
+/// ```
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct InputMap {
+    /// The bindings of each action, by name
+    pub actions: BTreeMap<String, Vec<InputBinding>>,
+    /// The bindings of each axis, by name
+    pub axes: BTreeMap<String, Vec<AxisBinding>>,
+    /// Gamepad axis values closer to zero than this are treated as zero
+    ///
+    /// **Default:** `0.15`
+    pub dead_zone: f32,
+    /// The multiplier for [`AxisBinding::MouseMotion`] values
+    ///
+    /// **Default:** `1.0`
+    pub mouse_sensitivity: f32,
+    /// The gamepad to read. If this is `None` every connected gamepad is read.
+    ///
+    /// **Default:** `None`
+    #[serde(skip)]
+    pub gamepad: Option<Gamepad>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            actions: default(),
+            axes: default(),
+            dead_zone: 0.15,
+            mouse_sensitivity: 1.0,
+            gamepad: None,
+        }
+    }
+}
+
+impl InputMap {
+    /// Add a binding to an action
+    pub fn bind_action(&mut self, action: &str, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Remove a binding from an action
+    pub fn unbind_action(&mut self, action: &str, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|x| *x != binding);
+        }
+        self
+    }
+
+    /// Replace all of the bindings of an action
+    pub fn set_action_bindings(
+        &mut self,
+        action: &str,
+        bindings: impl IntoIterator<Item = InputBinding>,
+    ) -> &mut Self {
+        self.actions
+            .insert(action.to_string(), bindings.into_iter().collect());
+        self
+    }
+
+    /// Get the bindings of an action
+    pub fn action_bindings(&self, action: &str) -> &[InputBinding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Add a binding to an axis
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        let bindings = self.axes.entry(axis.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Remove a binding from an axis
+    pub fn unbind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        if let Some(bindings) = self.axes.get_mut(axis) {
+            bindings.retain(|x| *x != binding);
+        }
+        self
+    }
+
+    /// Replace all of the bindings of an axis
+    pub fn set_axis_bindings(
+        &mut self,
+        axis: &str,
+        bindings: impl IntoIterator<Item = AxisBinding>,
+    ) -> &mut Self {
+        self.axes
+            .insert(axis.to_string(), bindings.into_iter().collect());
+        self
+    }
+
+    /// Get the bindings of an axis
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Add bindings from another input map for any actions and axes that this map doesn't have
+    ///
+    /// This is useful for adding the default bindings for new actions to bindings that were
+    /// saved by an older version of a game.
+    pub fn merge_defaults(&mut self, defaults: &InputMap) -> &mut Self {
+        for (action, bindings) in &defaults.actions {
+            self.actions
+                .entry(action.clone())
+                .or_insert_with(|| bindings.clone());
+        }
+        for (axis, bindings) in &defaults.axes {
+            self.axes
+                .entry(axis.clone())
+                .or_insert_with(|| bindings.clone());
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let mut input_map = InputMap {
+            dead_zone: 0.25,
+            mouse_sensitivity: 2.0,
+            gamepad: Some(Gamepad::new(1)),
+            ..default()
+        };
+        input_map
+            .bind_action("jump", KeyCode::Space)
+            .bind_action("jump", MouseButton::Left)
+            .bind_action("jump", GamepadButtonType::South)
+            .bind_axis(
+                "move_x",
+                AxisBinding::buttons(KeyCode::Left, KeyCode::Right),
+            )
+            .bind_axis(
+                "move_x",
+                AxisBinding::GamepadAxis(GamepadAxisType::LeftStickX),
+            )
+            .bind_axis("look_x", AxisBinding::MouseMotion(MouseAxis::X))
+            .bind_axis("zoom", AxisBinding::MouseWheel(MouseAxis::Y));
+
+        let saved = serde_json::to_string(&input_map).unwrap();
+        let loaded: InputMap = serde_json::from_str(&saved).unwrap();
+
+        // The gamepad isn't saved, because gamepad ids change between runs
+        assert_eq!(loaded.gamepad, None);
+        assert_eq!(
+            loaded,
+            InputMap {
+                gamepad: None,
+                ..input_map
+            }
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_defaulted() {
+        let loaded: InputMap = serde_json::from_str(r#"{"actions":{}}"#).unwrap();
+        assert_eq!(loaded, InputMap::default());
+    }
+}
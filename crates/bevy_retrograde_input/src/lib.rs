@@ -0,0 +1,79 @@
+//! Bevy Retrograde input action mapping
+//!
+//! Instead of checking for specific keys and buttons, systems read named actions and axes from
+//! the [`ActionState`]. The [`InputMap`] resource decides which keyboard, mouse and gamepad inputs
+//! control each action and axis, so that players can change them with an [`InputRebinding`] and
+//! the bindings can be saved with serde.
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_input::prelude::*;
+//! fn setup(mut input_map: ResMut<InputMap>) {
+//!     input_map
+//!         .bind_action("jump", KeyCode::Space)
+//!         .bind_axis("move_x", AxisBinding::buttons(KeyCode::Left, KeyCode::Right));
+//! }
+//!
+//! fn jump(actions: Res<ActionState>) {
+//!     if actions.just_pressed("jump") {
+//!         info!("Jump!");
+//!     }
+//! }
+//!
+//! // Bindings can be stored in any serde format
+//! fn save_bindings(input_map: Res<InputMap>) {
+//!     let saved = serde_json::to_string(&*input_map).unwrap();
+//!     let loaded: InputMap = serde_json::from_str(&saved).unwrap();
+//!     assert_eq!(loaded.actions, input_map.actions);
+//! }
+//! ```
+
+use bevy::{input::InputSystem, prelude::*};
+
+pub mod action_state;
+pub use action_state::*;
+
+pub mod bindings;
+pub use bindings::*;
+
+pub mod rebinding;
+pub use rebinding::*;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use crate::{
+        ActionState, AxisBinding, InputBinding, InputMap, InputRebinding, MouseAxis,
+        RebindFinished, RebindTarget, RetroInputPlugin, RetroInputSet,
+    };
+}
+
+/// System set that updates the [`ActionState`] and captures [`InputRebinding`]s, which runs in
+/// [`PreUpdate`] after Bevy's input systems
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetroInputSet;
+
+/// Input action mapping plugin for Bevy Retrograde
+///
+/// Inserts an empty [`InputMap`], unless one has already been inserted, and requires Bevy's
+/// [`InputPlugin`][bevy::input::InputPlugin].
+pub struct RetroInputPlugin;
+
+impl Plugin for RetroInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputMap>()
+            .init_resource::<ActionState>()
+            .init_resource::<InputRebinding>()
+            .add_event::<RebindFinished>()
+            .configure_set(PreUpdate, RetroInputSet.after(InputSystem))
+            .add_systems(
+                PreUpdate,
+                // The rebinding is captured after the actions are updated, so that the button
+                // that was pressed doesn't trigger the action that it was just bound to
+                (update_action_state, capture_rebinding)
+                    .chain()
+                    .in_set(RetroInputSet),
+            );
+    }
+}
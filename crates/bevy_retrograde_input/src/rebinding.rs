@@ -0,0 +1,291 @@
+//! Changing bindings while the game is running
+
+use bevy::{input::gamepad::GamepadButtonType, prelude::*};
+
+use crate::{action_state::InputSources, AxisBinding, InputBinding, InputMap};
+
+/// Resource used to rebind an action or axis to the next button that the player presses
+///
+/// Only one rebinding can be in progress at a time. When the player presses a button it replaces
+/// the target's bindings on the same kind of device, so rebinding the keyboard key for an action
+/// keeps its gamepad buttons, and a [`RebindFinished`] event is sent.
+///
+/// Pressing the [`cancel`][Self::cancel] binding stops the rebinding without changing anything.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_input::prelude::*;
+/// fn start_rebinding(keyboard: Res<Input<KeyCode>>, mut rebinding: ResMut<InputRebinding>) {
+///     if keyboard.just_pressed(KeyCode::F1) {
+///         rebinding.start(RebindTarget::Action("jump".into()));
+///     }
+/// }
+///
+/// fn show_rebound(mut events: EventReader<RebindFinished>) {
+///     for event in events.iter() {
+///         if let Some(binding) = event.binding {
+///             info!("{:?} is now bound to {:?}", event.target, binding);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct InputRebinding {
+    /// The binding that cancels the rebinding
+    ///
+    /// **Default:** [`KeyCode::Escape`]
+    pub cancel: Option<InputBinding>,
+    target: Option<RebindTarget>,
+}
+
+impl Default for InputRebinding {
+    fn default() -> Self {
+        Self {
+            cancel: Some(InputBinding::Key(KeyCode::Escape)),
+            target: None,
+        }
+    }
+}
+
+impl InputRebinding {
+    /// Bind the next button that is pressed to the target, replacing any rebinding in progress
+    pub fn start(&mut self, target: RebindTarget) {
+        self.target = Some(target);
+    }
+
+    /// Stop waiting for a button without changing any bindings
+    pub fn stop(&mut self) {
+        self.target = None;
+    }
+
+    /// The target that is waiting for a button, if any
+    pub fn target(&self) -> Option<&RebindTarget> {
+        self.target.as_ref()
+    }
+
+    /// Whether or not a rebinding is in progress
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+}
+
+/// What an [`InputRebinding`] changes the binding of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebindTarget {
+    /// The bindings of an action
+    Action(String),
+    /// The negative button of the [`AxisBinding::Buttons`] pairs of an axis
+    AxisNegative(String),
+    /// The positive button of the [`AxisBinding::Buttons`] pairs of an axis
+    AxisPositive(String),
+}
+
+/// Event sent when an [`InputRebinding`] has finished
+#[derive(Event, Debug, Clone)]
+pub struct RebindFinished {
+    /// What was rebound
+    pub target: RebindTarget,
+    /// The new binding, or `None` if the rebinding was cancelled
+    pub binding: Option<InputBinding>,
+}
+
+/// Whether two bindings are on the same kind of device, treating the keyboard and mouse as one
+fn same_device(a: &InputBinding, b: &InputBinding) -> bool {
+    let is_gamepad = |binding: &InputBinding| matches!(binding, InputBinding::GamepadButton(_));
+    is_gamepad(a) == is_gamepad(b)
+}
+
+impl InputMap {
+    /// Bind a button to a rebinding target, replacing the target's bindings on the same kind of
+    /// device
+    ///
+    /// Axes only have their button pairs changed. An axis without a button pair on the same kind
+    /// of device gets a new pair with an unbound other side.
+    pub fn rebind(&mut self, target: &RebindTarget, binding: InputBinding) -> &mut Self {
+        match target {
+            RebindTarget::Action(action) => {
+                let bindings = self.actions.entry(action.clone()).or_default();
+                bindings.retain(|x| !same_device(x, &binding));
+                bindings.push(binding);
+            }
+            RebindTarget::AxisNegative(axis) | RebindTarget::AxisPositive(axis) => {
+                let is_negative = matches!(target, RebindTarget::AxisNegative(_));
+                let bindings = self.axes.entry(axis.clone()).or_default();
+                let mut found = false;
+                for axis_binding in bindings.iter_mut() {
+                    if let AxisBinding::Buttons { negative, positive } = axis_binding {
+                        let side = if is_negative { negative } else { positive };
+                        if same_device(side, &binding) {
+                            *side = binding;
+                            found = true;
+                        }
+                    }
+                }
+
+                if !found {
+                    // Use a button that can't be pressed for the other side
+                    let unbound = match binding {
+                        InputBinding::GamepadButton(_) => {
+                            InputBinding::GamepadButton(GamepadButtonType::Other(u8::MAX))
+                        }
+                        _ => InputBinding::Key(KeyCode::Unlabeled),
+                    };
+                    bindings.push(if is_negative {
+                        AxisBinding::Buttons {
+                            negative: binding,
+                            positive: unbound,
+                        }
+                    } else {
+                        AxisBinding::Buttons {
+                            negative: unbound,
+                            positive: binding,
+                        }
+                    });
+                }
+            }
+        }
+        self
+    }
+}
+
+/// System that binds the button that was pressed to the [`InputRebinding`] target
+pub(crate) fn capture_rebinding(
+    mut rebinding: ResMut<InputRebinding>,
+    mut input_map: ResMut<InputMap>,
+    mut finished_events: EventWriter<RebindFinished>,
+    sources: InputSources,
+) {
+    let Some(target) = &rebinding.target else {
+        return;
+    };
+    let Some(binding) = sources.any_just_pressed(input_map.gamepad) else {
+        return;
+    };
+
+    let target = target.clone();
+    rebinding.target = None;
+    if rebinding.cancel == Some(binding) {
+        finished_events.send(RebindFinished {
+            target,
+            binding: None,
+        });
+        return;
+    }
+
+    input_map.rebind(&target, binding);
+    finished_events.send(RebindFinished {
+        target,
+        binding: Some(binding),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_an_action_replaces_bindings_on_the_same_device() {
+        let mut input_map = InputMap::default();
+        input_map
+            .bind_action("jump", KeyCode::Space)
+            .bind_action("jump", MouseButton::Left)
+            .bind_action("jump", GamepadButtonType::South);
+
+        // The keyboard and mouse count as one device, and the gamepad button is kept
+        input_map.rebind(&RebindTarget::Action("jump".into()), KeyCode::W.into());
+        assert_eq!(
+            input_map.action_bindings("jump"),
+            [
+                InputBinding::GamepadButton(GamepadButtonType::South),
+                InputBinding::Key(KeyCode::W),
+            ]
+        );
+
+        input_map.rebind(
+            &RebindTarget::Action("jump".into()),
+            GamepadButtonType::East.into(),
+        );
+        assert_eq!(
+            input_map.action_bindings("jump"),
+            [
+                InputBinding::Key(KeyCode::W),
+                InputBinding::GamepadButton(GamepadButtonType::East),
+            ]
+        );
+
+        // Actions that don't exist yet are created
+        input_map.rebind(&RebindTarget::Action("fire".into()), KeyCode::F.into());
+        assert_eq!(
+            input_map.action_bindings("fire"),
+            [InputBinding::Key(KeyCode::F)]
+        );
+    }
+
+    #[test]
+    fn rebinding_an_axis_side_keeps_the_other_side() {
+        let mut input_map = InputMap::default();
+        input_map
+            .bind_axis(
+                "move_x",
+                AxisBinding::buttons(KeyCode::Left, KeyCode::Right),
+            )
+            .bind_axis(
+                "move_x",
+                AxisBinding::buttons(GamepadButtonType::DPadLeft, GamepadButtonType::DPadRight),
+            );
+
+        input_map.rebind(
+            &RebindTarget::AxisNegative("move_x".into()),
+            KeyCode::A.into(),
+        );
+        assert_eq!(
+            input_map.axis_bindings("move_x"),
+            [
+                AxisBinding::buttons(KeyCode::A, KeyCode::Right),
+                AxisBinding::buttons(GamepadButtonType::DPadLeft, GamepadButtonType::DPadRight),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_an_axis_without_a_pair_leaves_the_other_side_unbound() {
+        let mut input_map = InputMap::default();
+        input_map.bind_axis(
+            "move_x",
+            AxisBinding::GamepadAxis(bevy::input::gamepad::GamepadAxisType::LeftStickX),
+        );
+
+        input_map.rebind(
+            &RebindTarget::AxisPositive("move_x".into()),
+            KeyCode::D.into(),
+        );
+        input_map.rebind(
+            &RebindTarget::AxisNegative("move_x".into()),
+            GamepadButtonType::DPadLeft.into(),
+        );
+        assert_eq!(
+            input_map.axis_bindings("move_x"),
+            [
+                AxisBinding::GamepadAxis(bevy::input::gamepad::GamepadAxisType::LeftStickX),
+                AxisBinding::buttons(KeyCode::Unlabeled, KeyCode::D),
+                AxisBinding::buttons(
+                    GamepadButtonType::DPadLeft,
+                    GamepadButtonType::Other(u8::MAX)
+                ),
+            ]
+        );
+
+        // Once there is a pair, its other side is rebound instead of adding another pair
+        input_map.rebind(
+            &RebindTarget::AxisNegative("move_x".into()),
+            KeyCode::A.into(),
+        );
+        assert_eq!(
+            input_map.axis_bindings("move_x")[1],
+            AxisBinding::buttons(KeyCode::A, KeyCode::D)
+        );
+        assert_eq!(input_map.axis_bindings("move_x").len(), 3);
+    }
+}
@@ -2,46 +2,144 @@
 //!
 //! This is a re-export of [`bevy_rapier2d`] with some of our own utilities added.
 
+use bevy::asset::HandleId;
 use bevy::prelude::*;
 use bevy::render::texture::Image;
+use bevy::utils::{HashMap, HashSet};
 use density_mesh_core::prelude::GenerateDensityMeshSettings;
 use density_mesh_core::prelude::PointsSeparation;
 
 pub use bevy_rapier2d;
-use bevy_rapier2d::prelude::*;
+use bevy_rapier2d::{prelude::*, rapier::parry::transformation::vhacd::VHACD};
+
+mod outline;
+use outline::{simplify_outline, AlphaMask};
 
 #[doc(hidden)]
 pub mod prelude {
     pub use crate::{
-        CollisionEventExt, RetroPhysicsPlugin, TesselatedCollider, TesselatedColliderConfig,
+        CollisionEventExt, PhysicsScale, RetroPhysicsPlugin, TesselatedCollider,
+        TesselatedColliderConfig, TesselatedColliderRegion, TesselationMode,
     };
     pub use bevy_rapier2d::prelude::*;
 }
 
 /// Physics plugin for Bevy Retrograde
+///
+/// Positions, sizes, and velocities are given to rapier in pixels, and are divided by the
+/// [`pixels_per_meter`][Self::pixels_per_meter] before they are simulated, so that densities,
+/// masses, and forces can be tuned in real-world units. The [`PhysicsScale`] resource converts
+/// between the two.
+///
+/// Unless a [`RapierConfiguration`] has already been inserted, the gravity is set to the earth's
+/// gravity at this scale.
 pub struct RetroPhysicsPlugin {
-    /// Used to calculate the physics scale.
+    /// The number of pixels in one meter of the physics simulation
+    ///
+    /// **Default:** `8.0`
     pub pixels_per_meter: f32,
 }
 
 impl Default for RetroPhysicsPlugin {
     fn default() -> Self {
         Self {
-            pixels_per_meter: 8.0,
+            pixels_per_meter: DEFAULT_PIXELS_PER_METER,
         }
     }
 }
 
 impl Plugin for RetroPhysicsPlugin {
     fn build(&self, app: &mut App) {
+        let scale = PhysicsScale::new(self.pixels_per_meter);
+        if !app.world.contains_resource::<RapierConfiguration>() {
+            app.insert_resource(RapierConfiguration {
+                gravity: scale.gravity(),
+                ..default()
+            });
+        }
         if !app.is_plugin_added::<RapierPhysicsPlugin<NoUserData>>() {
-            app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default());
+            app.add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(
+                self.pixels_per_meter,
+            ));
         }
+        app.insert_resource(scale);
 
         #[cfg(feature = "debug")]
         app.add_plugin(RapierDebugRenderPlugin::default());
 
-        app.add_systems(PostUpdate, generate_colliders);
+        app.init_resource::<TesselatedColliderCache>()
+            .add_systems(PostUpdate, generate_colliders);
+    }
+}
+
+/// The acceleration of gravity on the earth's surface, in meters per second squared
+pub const EARTH_GRAVITY: f32 = 9.81;
+
+/// The default number of pixels in one meter of the physics simulation
+pub const DEFAULT_PIXELS_PER_METER: f32 = 8.0;
+
+/// Resource for converting between pixels and the meters used by the physics simulation
+///
+/// This is inserted by the [`RetroPhysicsPlugin`] with its
+/// [`pixels_per_meter`][RetroPhysicsPlugin::pixels_per_meter].
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_physics::prelude::*;
+/// fn low_gravity(scale: Res<PhysicsScale>, mut config: ResMut<RapierConfiguration>) {
+///     // The moon's gravity, in pixels per second squared
+///     config.gravity = scale.acceleration_to_pixels(Vec2::new(0.0, -1.62));
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsScale {
+    /// The number of pixels in one meter
+    pub pixels_per_meter: f32,
+}
+
+impl PhysicsScale {
+    /// Create a physics scale with the given number of pixels per meter
+    pub fn new(pixels_per_meter: f32) -> Self {
+        Self { pixels_per_meter }
+    }
+
+    /// Convert a length in pixels to meters
+    pub fn to_meters(&self, pixels: f32) -> f32 {
+        pixels / self.pixels_per_meter
+    }
+
+    /// Convert a length in meters to pixels
+    pub fn to_pixels(&self, meters: f32) -> f32 {
+        meters * self.pixels_per_meter
+    }
+
+    /// Convert a position, size, or velocity in pixels to meters
+    pub fn vec_to_meters(&self, pixels: Vec2) -> Vec2 {
+        pixels / self.pixels_per_meter
+    }
+
+    /// Convert a position, size, or velocity in meters to pixels
+    pub fn vec_to_pixels(&self, meters: Vec2) -> Vec2 {
+        meters * self.pixels_per_meter
+    }
+
+    /// Convert an acceleration in meters per second squared to pixels per second squared, which is
+    /// the unit of the [`RapierConfiguration::gravity`]
+    pub fn acceleration_to_pixels(&self, meters_per_second_squared: Vec2) -> Vec2 {
+        self.vec_to_pixels(meters_per_second_squared)
+    }
+
+    /// The earth's gravity in pixels per second squared, pointing down
+    pub fn gravity(&self) -> Vec2 {
+        self.acceleration_to_pixels(Vec2::new(0.0, -EARTH_GRAVITY))
+    }
+}
+
+impl Default for PhysicsScale {
+    fn default() -> Self {
+        Self::new(DEFAULT_PIXELS_PER_METER)
     }
 }
 
@@ -76,17 +174,77 @@ impl CollisionEventExt for CollisionEvent {
     }
 }
 
-/// Create a convex hull [`CollisionShape`] from a sprite image based on it's alpha channel
+/// Create a [`Collider`] from a sprite image based on it's alpha channel, using the
+/// [`TesselationMode`] of the config
+///
+/// In the [`ConvexHull`][TesselationMode::ConvexHull] and
+/// [`ConvexDecomposition`][TesselationMode::ConvexDecomposition] modes, separate islands of opaque
+/// pixels get their own convex parts, which are combined into a compound collider.
 ///
-/// Returns [`None`] if a mesh for the given image could not be generated
+/// Returns [`None`] if a mesh for the given image could not be generated, such as when none of
+/// its pixels are opaque, or when every island is smaller than the
+/// [`min_island_area`][TesselatedColliderConfig::min_island_area]
 pub fn create_convex_collider_from_image(
     image: DynamicImage,
     tesselator_config: &TesselatedColliderConfig,
+) -> Option<Collider> {
+    let mask = AlphaMask::from_image(&image);
+    let mut islands = mask.islands();
+    islands.retain(|island| island.area >= tesselator_config.min_island_area);
+
+    // The islands are cropped to their bounding boxes, so their shapes are offset to stay centered
+    // on the whole image
+    let center = Vec2::new(mask.width as f32, mask.height as f32) / 2.0;
+    let mut parts = match tesselator_config.mode {
+        TesselationMode::ConvexHull => islands
+            .iter()
+            .filter_map(|island| {
+                // The image of the island has a border of one pixel around it
+                create_convex_hull_from_image(
+                    islands.island_mask(island).apply(&image, island.min),
+                    center - island.min.as_vec2() + 1.0,
+                    tesselator_config,
+                )
+            })
+            .collect::<Vec<_>>(),
+        TesselationMode::Polyline => {
+            let (vertices, indices, _) =
+                trace_outlines(&islands.mask(), center, tesselator_config.tolerance)?;
+            return Some(Collider::polyline(vertices, Some(indices)));
+        }
+        TesselationMode::ConvexDecomposition => islands
+            .iter()
+            .flat_map(|island| {
+                create_convex_decomposition_from_mask(
+                    &islands.island_mask(island),
+                    center - island.min.as_vec2(),
+                    tesselator_config,
+                )
+            })
+            .collect(),
+    };
+
+    if parts.len() > 1 {
+        Some(Collider::compound(
+            parts
+                .into_iter()
+                .map(|part| (Vect::ZERO, 0.0, part))
+                .collect(),
+        ))
+    } else {
+        parts.pop()
+    }
+}
+
+/// Create a convex hull around the opaque pixels of an image, centered on the given point of the
+/// image
+fn create_convex_hull_from_image(
+    image: DynamicImage,
+    center: Vec2,
+    tesselator_config: &TesselatedColliderConfig,
 ) -> Option<Collider> {
     use density_mesh_core::prelude::DensityMeshGenerator;
     use density_mesh_image::settings::GenerateDensityImageSettings;
-    let width = image.width();
-    let height = image.height();
     let density_map = density_mesh_image::generate_densitymap_from_image(
         image,
         &GenerateDensityImageSettings {
@@ -117,12 +275,7 @@ pub fn create_convex_collider_from_image(
     let points = density_mesh
         .points
         .iter()
-        .map(|point| {
-            Vec2::new(
-                (point.x - width as f32 / 2.0) + 0.5,
-                -(point.y - height as f32 / 2.0) - 0.5,
-            )
-        })
+        .map(|point| Vec2::new((point.x - center.x) + 0.5, -(point.y - center.y) - 0.5))
         .collect::<Vec<_>>();
 
     if tesselator_config.vertice_radius == 0.0 {
@@ -132,12 +285,124 @@ pub fn create_convex_collider_from_image(
     }
 }
 
+/// Trace the outlines of the opaque pixels in a mask, centered on the given point of the mask,
+/// returning the vertices and segments of a polyline, and the length of the outlines
+fn trace_outlines(
+    mask: &AlphaMask,
+    center: Vec2,
+    tolerance: f32,
+) -> Option<(Vec<Vec2>, Vec<[u32; 2]>, f32)> {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let mut perimeter = 0.0;
+    for outline in mask.outlines() {
+        let points = outline
+            .iter()
+            .map(|point| Vec2::new(point.x as f32 - center.x, center.y - point.y as f32))
+            .collect::<Vec<_>>();
+        let points = simplify_outline(&points, tolerance);
+        if points.len() < 3 {
+            continue;
+        }
+
+        let start = vertices.len() as u32;
+        for i in 0..points.len() {
+            let next = (i + 1) % points.len();
+            perimeter += points[i].distance(points[next]);
+            indices.push([start + i as u32, start + next as u32]);
+        }
+        vertices.extend(points);
+    }
+
+    if vertices.is_empty() {
+        None
+    } else {
+        Some((vertices, indices, perimeter))
+    }
+}
+
+/// Split the opaque pixels of a mask into convex parts, centered on the given point of the mask
+fn create_convex_decomposition_from_mask(
+    mask: &AlphaMask,
+    center: Vec2,
+    tesselator_config: &TesselatedColliderConfig,
+) -> Vec<Collider> {
+    let Some((vertices, indices, perimeter)) =
+        trace_outlines(mask, center, tesselator_config.tolerance)
+    else {
+        return Vec::new();
+    };
+
+    // The concavity is how much of the shape's convex hull a part may leave empty, so scale it by
+    // how much a gap of the tolerance along the outline would be
+    let concavity = (tesselator_config.tolerance * perimeter / mask.area() as f32).clamp(0.0, 1.0);
+    let params = VHACDParameters {
+        concavity,
+        ..default()
+    };
+    let points = vertices.into_iter().map(Into::into).collect::<Vec<_>>();
+    let decomposition = VHACD::decompose(&params, &points, &indices, false);
+
+    // The exact hulls used by `Collider::convex_decomposition` leave out the parts inside of the
+    // shape that don't touch the outline, so the voxelized hulls are used instead
+    decomposition
+        .compute_convex_hulls(1)
+        .into_iter()
+        .filter(|hull| hull.len() >= 3)
+        .filter_map(|hull| {
+            let hull = hull.into_iter().map(Into::into).collect::<Vec<_>>();
+            Collider::convex_hull(&hull)
+        })
+        .collect()
+}
+
+/// Marker for the [`TesselatedCollider`]s that have generated their collision shape, with the
+/// image and the region of it that the shape was generated from
 #[derive(Component)]
 #[component(storage = "SparseSet")]
-struct TesselatedColliderHasLoaded;
+struct TesselatedColliderHasLoaded {
+    texture: HandleId,
+    rect: Option<Rect>,
+}
+
+/// The collision shapes that have been generated from the regions of images, so that animations
+/// don't tesselate the same frames again every time that they are shown
+#[derive(Resource, Default)]
+struct TesselatedColliderCache {
+    shapes: HashMap<TesselatedShapeKey, Option<Collider>>,
+}
+
+/// The image, region, and config that a collision shape was generated from
+#[derive(PartialEq, Eq, Hash)]
+struct TesselatedShapeKey {
+    texture: HandleId,
+    rect: Option<[u32; 4]>,
+    /// The bits of the config's vertice separation, extrusion, vertice radius, and tolerance
+    config: [u32; 4],
+    mode: TesselationMode,
+    min_island_area: u32,
+}
+
+impl TesselatedShapeKey {
+    fn new(texture: HandleId, rect: Option<Rect>, config: &TesselatedColliderConfig) -> Self {
+        Self {
+            texture,
+            rect: rect
+                .map(|rect| [rect.min.x, rect.min.y, rect.max.x, rect.max.y].map(f32::to_bits)),
+            config: [
+                config.vertice_separation,
+                config.extrusion,
+                config.vertice_radius,
+                config.tolerance,
+            ]
+            .map(f32::to_bits),
+            mode: config.mode,
+            min_island_area: config.min_island_area,
+        }
+    }
+}
 
 use image::DynamicImage;
-use image::GenericImageView;
 use image::ImageBuffer;
 
 /// Sprite collision tesselator config
@@ -169,6 +434,24 @@ pub struct TesselatedColliderConfig {
     ///
     /// **Default:** `0.4`
     pub vertice_radius: f32,
+    /// How the collision shape is made from the image. The vertice separation, extrusion, and
+    /// vertice radius only apply to the [`ConvexHull`][TesselationMode::ConvexHull] mode.
+    ///
+    /// **Default:** [`TesselationMode::ConvexHull`]
+    pub mode: TesselationMode,
+    /// How far, in pixels, the traced outline of the image may be simplified in the concave modes.
+    /// Larger values give fewer vertices, and fewer parts in the
+    /// [`ConvexDecomposition`][TesselationMode::ConvexDecomposition] mode at the cost of the parts
+    /// covering more of the transparent pixels. A value of `0.0` follows the pixels exactly.
+    ///
+    /// **Default:** `1.0`
+    pub tolerance: f32,
+    /// Islands of opaque pixels that are connected by their sides or corners and have fewer pixels
+    /// than this are left out of the collision shape, which is useful for ignoring stray pixels and
+    /// small details such as sparkles around a sprite.
+    ///
+    /// **Default:** `0`
+    pub min_island_area: u32,
 }
 
 impl Default for TesselatedColliderConfig {
@@ -177,48 +460,415 @@ impl Default for TesselatedColliderConfig {
             vertice_separation: 10.,
             extrusion: 0.1,
             vertice_radius: 0.4,
+            mode: default(),
+            tolerance: 1.0,
+            min_island_area: 0,
         }
     }
 }
 
+/// How a [`TesselatedCollider`] makes its collision shape from the image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TesselationMode {
+    /// A single convex hull around all of the opaque pixels. Concave parts of the image, such as
+    /// the inside of an L or C shape, are filled in.
+    #[default]
+    ConvexHull,
+    /// A polyline that traces the outline of the opaque pixels, including any holes. Polylines
+    /// have no interior, so this is best for fixed bodies such as level geometry.
+    Polyline,
+    /// A compound of convex parts that together cover the opaque pixels, which works for concave
+    /// dynamic bodies. Holes in the image are filled in.
+    ConvexDecomposition,
+}
+
 /// A component used to automatically add a [`CollisionShape`] to an entity that is generated
 /// automatically by tesselating [`Image`] collision shape based on it's alpha channel
+///
+/// The collision shape is generated again whenever this component is changed, or when the image is
+/// modified, such as when it is hot reloaded.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde_physics::prelude::*;
+/// fn spawn_player(mut commands: Commands, atlas: Handle<TextureAtlas>) {
+///     commands.spawn((
+///         SpriteSheetBundle {
+///             texture_atlas: atlas,
+///             ..default()
+///         },
+///         // Generate the collision shape from the sprite that is being shown
+///         TesselatedCollider {
+///             region: TesselatedColliderRegion::AtlasSprite,
+///             ..default()
+///         },
+///         RigidBody::Dynamic,
+///     ));
+/// }
+/// ```
 #[derive(Default, Component)]
 pub struct TesselatedCollider {
+    /// The image to generate the collision shape from. This isn't used by the texture atlas
+    /// regions, which use the image of the atlas instead.
     pub texture: Handle<Image>,
+    /// The part of the image to generate the collision shape from
+    ///
+    /// **Default:** [`TesselatedColliderRegion::Whole`]
+    pub region: TesselatedColliderRegion,
     pub tesselator_config: TesselatedColliderConfig,
 }
 
+/// The part of an image that a [`TesselatedCollider`] generates its collision shape from
+///
+/// The collision shape is centered on the region, the same way that a sprite is centered on its
+/// texture or its sprite in a texture atlas.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TesselatedColliderRegion {
+    /// The whole image
+    #[default]
+    Whole,
+    /// A rectangle of the image, in pixels from the top left corner
+    Rect(Rect),
+    /// A sprite in a texture atlas
+    Atlas {
+        atlas: Handle<TextureAtlas>,
+        index: usize,
+    },
+    /// The sprite that the entity's [`TextureAtlasSprite`] is showing from its
+    /// [`Handle<TextureAtlas>`], which makes the collision shape follow the frames of an animation.
+    ///
+    /// The collision shape is only generated once the entity has both of these components.
+    AtlasSprite,
+}
+
+#[allow(clippy::type_complexity)]
 fn generate_colliders(
     mut commands: Commands,
-    pending_colliders: Query<(Entity, &TesselatedCollider), Without<TesselatedColliderHasLoaded>>,
+    tesselated_colliders: Query<(
+        Entity,
+        Ref<TesselatedCollider>,
+        Option<&TesselatedColliderHasLoaded>,
+        Option<(&Handle<TextureAtlas>, &TextureAtlasSprite)>,
+    )>,
     image_assets: Res<Assets<Image>>,
+    atlas_assets: Res<Assets<TextureAtlas>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut cache: ResMut<TesselatedColliderCache>,
 ) {
-    // TODO: Hot reload collision shape changes
-    for (ent, tesselated_collider) in pending_colliders.iter() {
+    let changed_images = image_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => Some(handle.id()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    if !changed_images.is_empty() {
+        cache
+            .shapes
+            .retain(|key, _| !changed_images.contains(&key.texture));
+    }
+
+    for (ent, tesselated_collider, has_loaded, sprite) in tesselated_colliders.iter() {
+        // Find the image and the region of it to use, which changes with the sprite's index
+        let region = match &tesselated_collider.region {
+            TesselatedColliderRegion::Whole => Some((&tesselated_collider.texture, None)),
+            TesselatedColliderRegion::Rect(rect) => {
+                Some((&tesselated_collider.texture, Some(*rect)))
+            }
+            TesselatedColliderRegion::Atlas { atlas, index } => {
+                get_atlas_region(&atlas_assets, atlas, *index)
+            }
+            TesselatedColliderRegion::AtlasSprite => sprite
+                .and_then(|(atlas, sprite)| get_atlas_region(&atlas_assets, atlas, sprite.index)),
+        };
+
         // Get the collider image
-        let image = if let Some(image) = image_assets.get(&tesselated_collider.texture) {
-            image
-        } else {
+        let Some((texture, rect, image)) =
+            region.and_then(|(texture, rect)| Some((texture, rect, image_assets.get(texture)?)))
+        else {
+            // Keep the collider pending until its new image or atlas has loaded
+            if has_loaded.is_some() {
+                commands.entity(ent).remove::<TesselatedColliderHasLoaded>();
+            }
             continue;
         };
 
-        let shape = create_convex_collider_from_image(
-            DynamicImage::ImageRgba8(
-                ImageBuffer::from_vec(
-                    image.texture_descriptor.size.width,
-                    image.texture_descriptor.size.height,
-                    image.data.clone(),
-                )
-                .unwrap(),
-            ),
-            &tesselated_collider.tesselator_config,
+        if let Some(has_loaded) = has_loaded {
+            if !tesselated_collider.is_changed()
+                && !changed_images.contains(&texture.id())
+                && has_loaded.texture == texture.id()
+                && has_loaded.rect == rect
+            {
+                continue;
+            }
+        }
+
+        let config = &tesselated_collider.tesselator_config;
+        let shape = cache
+            .shapes
+            .entry(TesselatedShapeKey::new(texture.id(), rect, config))
+            .or_insert_with(|| {
+                let shape = create_convex_collider_from_image(copy_region(image, rect), config);
+                if shape.is_none() {
+                    // Fully transparent images, such as empty animation frames, have no shape
+                    warn!(
+                        "Could not generate collision shape from image {:?} in region {:?}",
+                        texture, rect
+                    );
+                }
+                shape
+            });
+
+        let mut entity = commands.entity(ent);
+        if let Some(shape) = shape {
+            entity.insert(shape.clone());
+        } else {
+            entity.remove::<Collider>();
+        }
+        // Mark the collider as loaded even without a shape so that it isn't generated every frame
+        entity.insert(TesselatedColliderHasLoaded {
+            texture: texture.id(),
+            rect,
+        });
+    }
+}
+
+/// Copy a region of an image, leaving out any part of the region that is outside of the image
+fn copy_region(image: &Image, rect: Option<Rect>) -> DynamicImage {
+    let width = image.texture_descriptor.size.width;
+    let height = image.texture_descriptor.size.height;
+    let (min, max) = match rect {
+        Some(rect) => {
+            let size = Vec2::new(width as f32, height as f32);
+            let min = rect.min.clamp(Vec2::ZERO, size);
+            (min.as_uvec2(), rect.max.clamp(min, size).as_uvec2())
+        }
+        None => (UVec2::ZERO, UVec2::new(width, height)),
+    };
+
+    // Only copy the rows of the region, which are 4 bytes per pixel
+    let mut data = Vec::with_capacity(((max.x - min.x) * (max.y - min.y) * 4) as usize);
+    for y in min.y..max.y {
+        let row = (y * width) as usize * 4;
+        data.extend_from_slice(&image.data[row + min.x as usize * 4..row + max.x as usize * 4]);
+    }
+
+    DynamicImage::ImageRgba8(ImageBuffer::from_vec(max.x - min.x, max.y - min.y, data).unwrap())
+}
+
+/// Get the image of a texture atlas and the rect of one of its sprites, if the atlas has loaded
+fn get_atlas_region<'a>(
+    atlas_assets: &'a Assets<TextureAtlas>,
+    atlas: &Handle<TextureAtlas>,
+    index: usize,
+) -> Option<(&'a Handle<Image>, Option<Rect>)> {
+    let atlas = atlas_assets.get(atlas)?;
+    Some((&atlas.texture, Some(*atlas.textures.get(index)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn physics_scale_conversions() {
+        let scale = PhysicsScale::new(16.0);
+        assert_eq!(scale.to_meters(32.0), 2.0);
+        assert_eq!(scale.to_pixels(2.0), 32.0);
+        assert_eq!(
+            scale.vec_to_meters(Vec2::new(8.0, -16.0)),
+            Vec2::new(0.5, -1.0)
+        );
+        assert_eq!(
+            scale.vec_to_pixels(Vec2::new(0.5, -1.0)),
+            Vec2::new(8.0, -16.0)
+        );
+        assert_eq!(
+            scale.acceleration_to_pixels(Vec2::new(0.0, -1.5)),
+            Vec2::new(0.0, -24.0)
+        );
+        assert_eq!(scale.gravity(), Vec2::new(0.0, -EARTH_GRAVITY * 16.0));
+
+        assert_eq!(
+            PhysicsScale::default().pixels_per_meter,
+            RetroPhysicsPlugin::default().pixels_per_meter
+        );
+    }
+
+    /// Create an image from rows of pixels, where `#` is opaque
+    fn image(rows: &[&str]) -> DynamicImage {
+        DynamicImage::ImageRgba8(
+            ImageBuffer::from_vec(
+                rows[0].len() as u32,
+                rows.len() as u32,
+                rows.iter()
+                    .flat_map(|row| row.chars())
+                    .flat_map(|x| [255, 255, 255, if x == '#' { 255 } else { 0 }])
+                    .collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn config(mode: TesselationMode) -> TesselatedColliderConfig {
+        TesselatedColliderConfig { mode, ..default() }
+    }
+
+    #[test]
+    fn polyline_of_a_one_pixel_line() {
+        let collider = create_convex_collider_from_image(
+            image(&["..........", "##########", ".........."]),
+            &config(TesselationMode::Polyline),
+        )
+        .unwrap();
+        let polyline = collider.as_polyline().unwrap();
+        assert_eq!(polyline.indices().len(), 4);
+        let vertices = polyline.raw.vertices().iter().map(|x| Vec2::new(x.x, x.y));
+        assert_eq!(
+            vertices.fold(Vec2::ZERO, |size, x| size.max(x.abs())),
+            Vec2::new(5.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn islands_get_their_own_parts() {
+        let image = image(&["##......", "##......", "......##", "......##"]);
+        let config = TesselatedColliderConfig {
+            vertice_separation: 0.0,
+            ..config(TesselationMode::ConvexHull)
+        };
+        let collider = create_convex_collider_from_image(image, &config).unwrap();
+        let compound = collider.as_compound().unwrap();
+        let centers = compound
+            .raw
+            .shapes()
+            .iter()
+            .map(|(_, shape)| Vec2::from(shape.compute_local_aabb().center().coords))
+            .collect::<Vec<_>>();
+
+        // The parts are centered on the whole image, not on their islands
+        assert_eq!(centers.len(), 2);
+        for (center, expected) in centers
+            .iter()
+            .zip([Vec2::new(-3.0, 1.0), Vec2::new(3.0, -1.0)])
+        {
+            assert!(
+                center.distance(expected) < 0.01,
+                "{} != {}",
+                center,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn small_islands_are_left_out() {
+        let image = || image(&["##....", "##...#", "......"]);
+        let mut config = config(TesselationMode::Polyline);
+        config.min_island_area = 2;
+        let collider = create_convex_collider_from_image(image(), &config).unwrap();
+        assert_eq!(collider.as_polyline().unwrap().indices().len(), 4);
+
+        config.min_island_area = 5;
+        for mode in [
+            TesselationMode::ConvexHull,
+            TesselationMode::Polyline,
+            TesselationMode::ConvexDecomposition,
+        ] {
+            config.mode = mode;
+            assert!(create_convex_collider_from_image(image(), &config).is_none());
+        }
+    }
+
+    #[test]
+    fn transparent_images_have_no_shape() {
+        for mode in [
+            TesselationMode::ConvexHull,
+            TesselationMode::Polyline,
+            TesselationMode::ConvexDecomposition,
+        ] {
+            assert!(
+                create_convex_collider_from_image(image(&["...", "..."]), &config(mode)).is_none()
+            );
+        }
+    }
+
+    /// Create an [`Image`] asset from rows of pixels, where `#` is opaque
+    fn image_asset(rows: &[&str]) -> Image {
+        use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+        Image::new(
+            Extent3d {
+                width: rows[0].len() as u32,
+                height: rows.len() as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            image(rows).to_rgba8().into_raw(),
+            TextureFormat::Rgba8UnormSrgb,
         )
-        .expect("Could not generate collision shape from image");
+    }
+
+    #[test]
+    fn regions_are_clamped_to_the_image() {
+        let image = image_asset(&["#..", ".#.", "..#"]);
+        let region = copy_region(&image, Some(Rect::new(1.0, -2.0, 5.0, 2.0)));
+        assert_eq!(region.to_rgba8(), self::image(&["..", "#."]).to_rgba8());
+
+        let region = copy_region(&image, Some(Rect::new(4.0, 4.0, 6.0, 6.0)));
+        assert_eq!(region.to_rgba8().dimensions(), (0, 0));
+    }
+
+    #[test]
+    fn shapes_are_cached() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .init_resource::<TesselatedColliderCache>()
+            .add_systems(Update, generate_colliders);
+
+        let texture = app.world.resource_mut::<Assets<Image>>().add(image_asset(&[
+            "####....", "####....", "####....", "####....",
+        ]));
+        let collider = |rect| TesselatedCollider {
+            texture: texture.clone(),
+            region: TesselatedColliderRegion::Rect(rect),
+            tesselator_config: TesselatedColliderConfig {
+                vertice_separation: 0.0,
+                ..default()
+            },
+        };
+        let opaque = Rect::new(0.0, 0.0, 4.0, 4.0);
+        let transparent = Rect::new(4.0, 0.0, 8.0, 4.0);
+        let entities = [
+            app.world.spawn(collider(opaque)).id(),
+            app.world.spawn(collider(opaque)).id(),
+            app.world.spawn(collider(transparent)).id(),
+        ];
+        app.update();
+
+        // The transparent region has no shape, but isn't generated again
+        let world = &app.world;
+        assert_eq!(world.resource::<TesselatedColliderCache>().shapes.len(), 2);
+        assert!(world.get::<Collider>(entities[0]).is_some());
+        assert!(world.get::<Collider>(entities[1]).is_some());
+        assert!(world.get::<Collider>(entities[2]).is_none());
+        assert!(world
+            .get::<TesselatedColliderHasLoaded>(entities[2])
+            .is_some());
 
-        commands
-            .entity(ent)
-            .insert(shape)
-            .insert(TesselatedColliderHasLoaded);
+        // Modifying the image drops its shapes
+        app.world
+            .resource_mut::<Assets<Image>>()
+            .get_mut(&texture)
+            .unwrap();
+        app.world.entity_mut(entities[2]).despawn();
+        app.update();
+        app.update();
+        let shapes = &app.world.resource::<TesselatedColliderCache>().shapes;
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes.values().all(Option::is_some));
     }
 }
@@ -0,0 +1,550 @@
+//! Tracing the outlines of the opaque pixels in an image
+
+use bevy::{prelude::*, utils::HashMap};
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+/// Which pixels of an image are opaque
+pub(crate) struct AlphaMask {
+    pub width: u32,
+    pub height: u32,
+    opaque: Vec<bool>,
+}
+
+impl AlphaMask {
+    /// Create a mask of the pixels with any opacity
+    pub fn from_image(image: &DynamicImage) -> Self {
+        let (width, height) = image.dimensions();
+        let opaque = image
+            .to_rgba8()
+            .pixels()
+            .map(|pixel| pixel.0[3] > 0)
+            .collect();
+
+        Self {
+            width,
+            height,
+            opaque,
+        }
+    }
+
+    /// Whether or not a pixel is opaque. Pixels outside of the image are transparent.
+    pub fn is_opaque(&self, x: i32, y: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as u32) < self.width
+            && (y as u32) < self.height
+            && self.opaque[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    /// The number of opaque pixels
+    pub fn area(&self) -> u32 {
+        self.opaque.iter().filter(|x| **x).count() as u32
+    }
+
+    /// Find the groups of opaque pixels that are connected by their sides or corners
+    pub fn islands(&self) -> Islands {
+        let mut labels = vec![0; self.opaque.len()];
+        let mut islands = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.opaque.len() {
+            if labels[start] != 0 || !self.opaque[start] {
+                continue;
+            }
+
+            let label = islands.len() as u32 + 1;
+            let position = UVec2::new(start as u32 % self.width, start as u32 / self.width);
+            let mut island = Island {
+                label,
+                area: 0,
+                min: position,
+                max: position + 1,
+            };
+            labels[start] = label;
+            stack.push(start);
+            while let Some(i) = stack.pop() {
+                let position = UVec2::new(i as u32 % self.width, i as u32 / self.width);
+                island.area += 1;
+                island.min = island.min.min(position);
+                island.max = island.max.max(position + 1);
+
+                let (x, y) = (position.x as i32, position.y as i32);
+                for (x, y) in [
+                    (x - 1, y - 1),
+                    (x, y - 1),
+                    (x + 1, y - 1),
+                    (x - 1, y),
+                    (x + 1, y),
+                    (x - 1, y + 1),
+                    (x, y + 1),
+                    (x + 1, y + 1),
+                ] {
+                    if self.is_opaque(x, y) {
+                        let neighbor = (y as u32 * self.width + x as u32) as usize;
+                        if labels[neighbor] == 0 {
+                            labels[neighbor] = label;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+            islands.push(island);
+        }
+
+        Islands {
+            width: self.width,
+            height: self.height,
+            labels,
+            islands,
+        }
+    }
+
+    /// Copy the pixels of an image that are in the mask, with the mask's top left corner at the
+    /// given origin of the image
+    ///
+    /// The copy has a fully transparent border of one pixel, so that the edges of the opaque pixels
+    /// can be found even where they touch the edges of the mask.
+    pub fn apply(&self, image: &DynamicImage, origin: UVec2) -> DynamicImage {
+        let mut copy = RgbaImage::new(self.width + 2, self.height + 2);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.opaque[(y * self.width + x) as usize] {
+                    copy.put_pixel(x + 1, y + 1, image.get_pixel(origin.x + x, origin.y + y));
+                }
+            }
+        }
+        DynamicImage::ImageRgba8(copy)
+    }
+
+    /// Trace the outlines around the opaque pixels along the pixel edges, in image coordinates
+    ///
+    /// Each outline is a closed loop, with the last point connecting back to the first. Holes in
+    /// the opaque pixels get their own outlines.
+    pub fn outlines(&self) -> Vec<Vec<IVec2>> {
+        // Every pixel side between an opaque and a transparent pixel is an edge, going clockwise
+        // around the opaque pixel
+        let mut edges = Vec::new();
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                if !self.is_opaque(x, y) {
+                    continue;
+                }
+                if !self.is_opaque(x, y - 1) {
+                    edges.push((IVec2::new(x, y), IVec2::new(x + 1, y)));
+                }
+                if !self.is_opaque(x + 1, y) {
+                    edges.push((IVec2::new(x + 1, y), IVec2::new(x + 1, y + 1)));
+                }
+                if !self.is_opaque(x, y + 1) {
+                    edges.push((IVec2::new(x + 1, y + 1), IVec2::new(x, y + 1)));
+                }
+                if !self.is_opaque(x - 1, y) {
+                    edges.push((IVec2::new(x, y + 1), IVec2::new(x, y)));
+                }
+            }
+        }
+
+        let mut outgoing: HashMap<IVec2, Vec<usize>> = HashMap::default();
+        for (i, (start, _)) in edges.iter().enumerate() {
+            outgoing.entry(*start).or_default().push(i);
+        }
+
+        let mut visited = vec![false; edges.len()];
+        let mut outlines = Vec::new();
+        for first in 0..edges.len() {
+            if visited[first] {
+                continue;
+            }
+
+            let mut outline = Vec::new();
+            let mut current = first;
+            loop {
+                visited[current] = true;
+                let (start, end) = edges[current];
+                outline.push(start);
+
+                let direction = end - start;
+                // Where two pixels only touch at a corner, keep going around the same pixel so
+                // that they get separate outlines
+                let next = outgoing[&end]
+                    .iter()
+                    .copied()
+                    .max_by_key(|x| {
+                        let next_direction = edges[*x].1 - edges[*x].0;
+                        direction.perp_dot(next_direction)
+                    })
+                    .expect("Pixel outlines are closed");
+                if visited[next] {
+                    break;
+                }
+                current = next;
+            }
+            outlines.push(outline);
+        }
+
+        outlines
+    }
+}
+
+/// The groups of opaque pixels in an [`AlphaMask`] that are connected by their sides or corners
+pub(crate) struct Islands {
+    width: u32,
+    height: u32,
+    /// The label of the island that each pixel is in, or `0` for transparent pixels
+    labels: Vec<u32>,
+    /// The islands, in the order of their labels
+    islands: Vec<Island>,
+}
+
+/// A group of connected opaque pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Island {
+    label: u32,
+    /// The number of pixels in the island
+    pub area: u32,
+    /// The top left corner of the island's bounding box
+    pub min: UVec2,
+    /// The bottom right corner of the island's bounding box, which is outside of the island
+    pub max: UVec2,
+}
+
+impl Islands {
+    pub fn iter(&self) -> impl Iterator<Item = &Island> {
+        self.islands.iter()
+    }
+
+    /// Only keep the islands that match the predicate
+    pub fn retain(&mut self, predicate: impl FnMut(&Island) -> bool) {
+        self.islands.retain(predicate);
+    }
+
+    /// A mask of the pixels in all of the islands
+    pub fn mask(&self) -> AlphaMask {
+        let mut kept = vec![false; self.islands.last().map_or(1, |x| x.label as usize + 1)];
+        for island in &self.islands {
+            kept[island.label as usize] = true;
+        }
+
+        AlphaMask {
+            width: self.width,
+            height: self.height,
+            opaque: self
+                .labels
+                .iter()
+                .map(|x| kept.get(*x as usize).copied().unwrap_or(false))
+                .collect(),
+        }
+    }
+
+    /// A mask of the pixels in an island, cropped to its bounding box
+    pub fn island_mask(&self, island: &Island) -> AlphaMask {
+        let Island { min, max, .. } = *island;
+        AlphaMask {
+            width: max.x - min.x,
+            height: max.y - min.y,
+            opaque: (min.y..max.y)
+                .flat_map(|y| {
+                    (min.x..max.x)
+                        .map(move |x| self.labels[(y * self.width + x) as usize] == island.label)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Remove the points of a closed outline that are on a straight line between their neighbors, and
+/// then simplify it so that it moves by no more than the tolerance
+///
+/// Outlines that are too thin to keep any area after simplifying, such as the outline of a line
+/// that is one pixel thick, are only stripped of their straight line points.
+pub(crate) fn simplify_outline(outline: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let len = outline.len();
+    let points: Vec<Vec2> = (0..len)
+        .filter(|i| {
+            let previous = outline[(i + len - 1) % len];
+            let next = outline[(i + 1) % len];
+            (outline[*i] - previous).perp_dot(next - outline[*i]).abs() > f32::EPSILON
+        })
+        .map(|i| outline[i])
+        .collect();
+
+    if tolerance <= 0.0 || points.len() <= 3 {
+        return points;
+    }
+
+    // Split the loop at the point furthest from the first one, and simplify each half
+    let furthest = (1..points.len())
+        .max_by(|a, b| {
+            points[0]
+                .distance_squared(points[*a])
+                .total_cmp(&points[0].distance_squared(points[*b]))
+        })
+        .unwrap_or(1);
+    let mut closed = points.clone();
+    closed.push(points[0]);
+    let mut simplified = douglas_peucker(&closed[..=furthest], tolerance);
+    simplified.pop();
+    simplified.extend(douglas_peucker(&closed[furthest..], tolerance));
+    simplified.pop();
+
+    if simplified.len() < 3 {
+        points
+    } else {
+        simplified
+    }
+}
+
+/// Simplify an open line with the Ramer–Douglas–Peucker algorithm
+fn douglas_peucker(points: &[Vec2], tolerance: f32) -> Vec<Vec2> {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let distance = |point: Vec2| {
+        let line = last - first;
+        if line.length_squared() == 0.0 {
+            point.distance(first)
+        } else {
+            line.perp_dot(point - first).abs() / line.length()
+        }
+    };
+
+    let furthest =
+        (1..points.len() - 1).max_by(|a, b| distance(points[*a]).total_cmp(&distance(points[*b])));
+    match furthest {
+        Some(i) if distance(points[i]) > tolerance => {
+            let mut simplified = douglas_peucker(&points[..=i], tolerance);
+            simplified.pop();
+            simplified.extend(douglas_peucker(&points[i..], tolerance));
+            simplified
+        }
+        _ => vec![first, last],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a mask from rows of pixels, where `#` is opaque
+    fn mask(rows: &[&str]) -> AlphaMask {
+        AlphaMask {
+            width: rows[0].len() as u32,
+            height: rows.len() as u32,
+            opaque: rows
+                .iter()
+                .flat_map(|row| row.chars().map(|x| x == '#'))
+                .collect(),
+        }
+    }
+
+    fn points(points: &[(i32, i32)]) -> Vec<IVec2> {
+        points.iter().map(|(x, y)| IVec2::new(*x, *y)).collect()
+    }
+
+    /// The outlines with the points on straight lines removed
+    fn corners(mask: &AlphaMask) -> Vec<Vec<Vec2>> {
+        mask.outlines()
+            .iter()
+            .map(|outline| {
+                let outline = outline.iter().map(|x| x.as_vec2()).collect::<Vec<_>>();
+                simplify_outline(&outline, 0.0)
+            })
+            .collect()
+    }
+
+    /// The area of an outline, which is positive for outlines that go clockwise in image
+    /// coordinates
+    fn signed_area(outline: &[Vec2]) -> f32 {
+        (0..outline.len())
+            .map(|i| outline[i].perp_dot(outline[(i + 1) % outline.len()]))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    #[test]
+    fn l_shape() {
+        let mask = mask(&["#.", "##"]);
+        assert_eq!(
+            mask.outlines(),
+            [points(&[
+                (0, 0),
+                (1, 0),
+                (1, 1),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1)
+            ])]
+        );
+        assert_eq!(
+            corners(&mask),
+            [[(0., 0.), (1., 0.), (1., 1.), (2., 1.), (2., 2.), (0., 2.)].map(Vec2::from)]
+        );
+    }
+
+    #[test]
+    fn ring_with_a_hole() {
+        let mask = mask(&["###", "#.#", "###"]);
+        let outlines = corners(&mask);
+        assert_eq!(
+            outlines,
+            [
+                [(0., 0.), (3., 0.), (3., 3.), (0., 3.)]
+                    .map(Vec2::from)
+                    .to_vec(),
+                [(2., 1.), (1., 1.), (1., 2.), (2., 2.)]
+                    .map(Vec2::from)
+                    .to_vec(),
+            ]
+        );
+
+        // Holes wind the other way around
+        assert_eq!(signed_area(&outlines[0]), 9.0);
+        assert_eq!(signed_area(&outlines[1]), -1.0);
+    }
+
+    #[test]
+    fn pixels_touching_at_a_corner() {
+        let mask = mask(&["#.", ".#"]);
+        assert_eq!(
+            mask.outlines(),
+            [
+                points(&[(0, 0), (1, 0), (1, 1), (0, 1)]),
+                points(&[(1, 1), (2, 1), (2, 2), (1, 2)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn one_pixel_line() {
+        let mask = mask(&["##########"]);
+        let outline = mask.outlines()[0]
+            .iter()
+            .map(|x| x.as_vec2())
+            .collect::<Vec<_>>();
+        assert_eq!(outline.len(), 22);
+
+        // Simplifying would leave a line with no area, so only the corners are kept
+        assert_eq!(
+            simplify_outline(&outline, 1.0),
+            [(0., 0.), (10., 0.), (10., 1.), (0., 1.)].map(Vec2::from)
+        );
+    }
+
+    #[test]
+    fn transparent_image() {
+        let mask = mask(&["...", "..."]);
+        assert_eq!(mask.area(), 0);
+        assert!(mask.outlines().is_empty());
+    }
+
+    #[test]
+    fn simplified_outlines_stay_within_the_tolerance() {
+        let mask = mask(&["#.....", "##....", "###...", "####..", "#####.", "######"]);
+        let outline = mask.outlines()[0]
+            .iter()
+            .map(|x| x.as_vec2())
+            .collect::<Vec<_>>();
+        let simplified = simplify_outline(&outline, 1.0);
+        assert!(simplified.len() < simplify_outline(&outline, 0.0).len());
+        assert!(simplified.len() >= 3);
+        assert_eq!(simplified[0], outline[0]);
+
+        // Every point of the outline is close to an edge of the simplified outline
+        let distance_to_segment = |point: Vec2, a: Vec2, b: Vec2| {
+            let t = ((point - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+            point.distance(a + (b - a) * t)
+        };
+        for point in &outline {
+            let distance = (0..simplified.len())
+                .map(|i| {
+                    distance_to_segment(
+                        *point,
+                        simplified[i],
+                        simplified[(i + 1) % simplified.len()],
+                    )
+                })
+                .fold(f32::INFINITY, f32::min);
+            assert!(
+                distance <= 1.0,
+                "{} is {} from the outline",
+                point,
+                distance
+            );
+        }
+    }
+
+    #[test]
+    fn islands_connect_at_corners() {
+        let mask = mask(&["#..##", ".#...", "....#"]);
+        let islands = mask.islands();
+        assert_eq!(
+            islands
+                .iter()
+                .map(|x| (x.area, x.min, x.max))
+                .collect::<Vec<_>>(),
+            [
+                (2, UVec2::new(0, 0), UVec2::new(2, 2)),
+                (2, UVec2::new(3, 0), UVec2::new(5, 1)),
+                (1, UVec2::new(4, 2), UVec2::new(5, 3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn island_masks_are_cropped() {
+        // The bounding boxes of the islands overlap, but their masks only have their own pixels
+        let islands = mask(&["######", "#.....", "#..##.", "#...#.", "#.....", "######"]).islands();
+        let islands = islands
+            .iter()
+            .map(|island| {
+                let mask = islands.island_mask(island);
+                (island.min, mask.width, mask.height, mask.opaque)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(islands.len(), 2);
+        assert_eq!(islands[0].0, UVec2::ZERO);
+        assert_eq!(islands[0].1, 6);
+        assert_eq!(islands[0].2, 6);
+        assert_eq!(
+            islands[0].3,
+            mask(&["######", "#.....", "#.....", "#.....", "#.....", "######"]).opaque
+        );
+        assert_eq!(islands[1].0, UVec2::new(3, 2));
+        assert_eq!(islands[1].3, mask(&["##", ".#"]).opaque);
+    }
+
+    #[test]
+    fn retained_islands_mask() {
+        let mut islands = mask(&["##..#", "##...", "....#"]).islands();
+        islands.retain(|island| island.area > 1);
+        assert_eq!(
+            islands.mask().opaque,
+            mask(&["##...", "##...", "....."]).opaque
+        );
+
+        islands.retain(|_| false);
+        assert_eq!(islands.mask().area(), 0);
+    }
+
+    #[test]
+    fn masks_are_applied_to_cropped_images() {
+        let image = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(4, 3, |x, y| {
+            image::Rgba([x as u8, y as u8, 0, 255])
+        }));
+        let cropped = mask(&["#.", "##"]).apply(&image, UVec2::new(1, 1));
+        assert_eq!(cropped.dimensions(), (4, 4));
+        let pixels = cropped.to_rgba8();
+        let pixels = pixels
+            .rows()
+            .map(|row| row.map(|x| x.0).collect::<Vec<_>>());
+        let t = [0; 4];
+        assert_eq!(
+            pixels.collect::<Vec<_>>(),
+            [
+                [t, t, t, t],
+                [t, [1, 1, 0, 255], t, t],
+                [t, [1, 2, 0, 255], [2, 2, 0, 255], t],
+                [t, t, t, t],
+            ]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Save data storage backends
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::*;
+
+use crate::SaveError;
+
+/// A place that save slots can be stored in
+///
+/// Slots are stored as strings, identified by their slot names.
+pub trait SaveBackend: Send + Sync + 'static {
+    /// Read the data in a slot, or `None` if the slot doesn't exist
+    fn read(&self, slot: &str) -> Result<Option<String>, SaveError>;
+    /// Write data to a slot, replacing any data that was already in it
+    fn write(&self, slot: &str, data: &str) -> Result<(), SaveError>;
+    /// Delete a slot. Deleting a slot that doesn't exist is not an error.
+    fn delete(&self, slot: &str) -> Result<(), SaveError>;
+    /// List the names of all of the slots in the backend
+    fn slots(&self) -> Result<Vec<String>, SaveError>;
+}
+
+/// Resource containing the [`SaveBackend`] that save slots are stored in
+///
+/// This is inserted by the [`RetroSavePlugin`][crate::RetroSavePlugin] with the default backend for
+/// the platform, and may be replaced to use a different backend.
+#[derive(Resource)]
+pub struct SaveStorage(pub Box<dyn SaveBackend>);
+
+impl SaveStorage {
+    /// Store save slots in the given backend
+    pub fn new<B: SaveBackend>(backend: B) -> Self {
+        Self(Box::new(backend))
+    }
+
+    /// Get the default backend for the platform, storing slots under the given app name
+    ///
+    /// This is a [`FileBackend`] on desktop and a [`LocalStorageBackend`] on the web.
+    pub fn platform_default(app_name: &str) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Self::new(FileBackend::new(app_name));
+
+        #[cfg(target_arch = "wasm32")]
+        return Self::new(LocalStorageBackend::new(app_name));
+    }
+}
+
+impl std::ops::Deref for SaveStorage {
+    type Target = dyn SaveBackend;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+/// Make sure a slot name can't be used to escape the save directory or storage prefix
+fn validate_slot_name(slot: &str) -> Result<(), SaveError> {
+    let valid = !slot.is_empty()
+        && slot
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(SaveError::InvalidSlotName(slot.to_string()))
+    }
+}
+
+/// Backend that stores save slots as files in a directory
+///
+/// By default, the directory is in the platform's data directory, such as
+/// `~/.local/share/{app_name}/saves` on Linux or `%APPDATA%\{app_name}\saves` on Windows.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileBackend {
+    /// The directory that the save files are stored in
+    pub directory: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileBackend {
+    /// The file extension of save files
+    const EXTENSION: &'static str = "json";
+
+    /// Store save slots in the platform's data directory, under the given app name
+    pub fn new(app_name: &str) -> Self {
+        let data_dir = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+
+        Self {
+            directory: data_dir.join(app_name).join("saves"),
+        }
+    }
+
+    fn slot_path(&self, slot: &str) -> Result<std::path::PathBuf, SaveError> {
+        validate_slot_name(slot)?;
+
+        Ok(self.directory.join(slot).with_extension(Self::EXTENSION))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SaveBackend for FileBackend {
+    fn read(&self, slot: &str) -> Result<Option<String>, SaveError> {
+        match std::fs::read_to_string(self.slot_path(slot)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, slot: &str, data: &str) -> Result<(), SaveError> {
+        let path = self.slot_path(slot)?;
+        std::fs::create_dir_all(&self.directory)?;
+
+        // Write to a temporary file first so that a crash can't leave a half-written save
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, data)?;
+        std::fs::rename(&temp_path, &path)?;
+
+        Ok(())
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        match std::fs::remove_file(self.slot_path(slot)?) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn slots(&self) -> Result<Vec<String>, SaveError> {
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut slots = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|x| x.to_str()) != Some(Self::EXTENSION) {
+                continue;
+            }
+            if let Some(slot) = path.file_stem().and_then(|x| x.to_str()) {
+                slots.push(slot.to_string());
+            }
+        }
+        slots.sort();
+
+        Ok(slots)
+    }
+}
+
+/// Backend that stores save slots in the browser's `localStorage`
+///
+/// Each slot is stored under a key made of the prefix followed by the slot name.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone)]
+pub struct LocalStorageBackend {
+    /// The prefix added to the `localStorage` key of every slot
+    pub prefix: String,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageBackend {
+    /// Store save slots in `localStorage`, prefixed by the given app name
+    pub fn new(app_name: &str) -> Self {
+        Self {
+            prefix: format!("{}.saves.", app_name),
+        }
+    }
+
+    fn storage(&self) -> Result<web_sys::Storage, SaveError> {
+        web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .ok_or_else(|| SaveError::Storage("localStorage is not available".into()))
+    }
+
+    fn key(&self, slot: &str) -> Result<String, SaveError> {
+        validate_slot_name(slot)?;
+
+        Ok(format!("{}{}", self.prefix, slot))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_error(error: wasm_bindgen::JsValue) -> SaveError {
+    SaveError::Storage(format!("{:?}", error))
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SaveBackend for LocalStorageBackend {
+    fn read(&self, slot: &str) -> Result<Option<String>, SaveError> {
+        self.storage()?
+            .get_item(&self.key(slot)?)
+            .map_err(storage_error)
+    }
+
+    fn write(&self, slot: &str, data: &str) -> Result<(), SaveError> {
+        self.storage()?
+            .set_item(&self.key(slot)?, data)
+            .map_err(storage_error)
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        self.storage()?
+            .remove_item(&self.key(slot)?)
+            .map_err(storage_error)
+    }
+
+    fn slots(&self) -> Result<Vec<String>, SaveError> {
+        let storage = self.storage()?;
+        let length = storage.length().map_err(storage_error)?;
+
+        let mut slots = Vec::new();
+        for i in 0..length {
+            if let Some(key) = storage.key(i).map_err(storage_error)? {
+                if let Some(slot) = key.strip_prefix(&self.prefix) {
+                    slots.push(slot.to_string());
+                }
+            }
+        }
+        slots.sort();
+
+        Ok(slots)
+    }
+}
+
+/// Backend that keeps save slots in memory, which is useful for tests
+///
+/// Clones of the backend share the same slots, so a clone can be kept to inspect the slots after
+/// the backend has been inserted into the [`SaveStorage`].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryBackend {
+    slots: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl SaveBackend for MemoryBackend {
+    fn read(&self, slot: &str) -> Result<Option<String>, SaveError> {
+        validate_slot_name(slot)?;
+
+        Ok(self.slots.lock().unwrap().get(slot).cloned())
+    }
+
+    fn write(&self, slot: &str, data: &str) -> Result<(), SaveError> {
+        validate_slot_name(slot)?;
+        self.slots
+            .lock()
+            .unwrap()
+            .insert(slot.to_string(), data.to_string());
+
+        Ok(())
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        validate_slot_name(slot)?;
+        self.slots.lock().unwrap().remove(slot);
+
+        Ok(())
+    }
+
+    fn slots(&self) -> Result<Vec<String>, SaveError> {
+        Ok(self.slots.lock().unwrap().keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_backend_round_trip() {
+        let backend = MemoryBackend::default();
+        assert_eq!(backend.read("slot1").unwrap(), None);
+
+        backend.write("slot1", "data").unwrap();
+        backend.write("slot 2", "more data").unwrap();
+        assert_eq!(backend.read("slot1").unwrap().as_deref(), Some("data"));
+        assert_eq!(backend.slots().unwrap(), ["slot 2", "slot1"]);
+
+        backend.delete("slot1").unwrap();
+        backend.delete("slot1").unwrap();
+        assert_eq!(backend.read("slot1").unwrap(), None);
+        assert_eq!(backend.slots().unwrap(), ["slot 2"]);
+    }
+
+    #[test]
+    fn memory_backend_validates_slot_names() {
+        let backend = MemoryBackend::default();
+        for slot in ["", "../escape", "a/b", "a.json"] {
+            assert!(matches!(
+                backend.write(slot, "data"),
+                Err(SaveError::InvalidSlotName(_))
+            ));
+            assert!(matches!(
+                backend.read(slot),
+                Err(SaveError::InvalidSlotName(_))
+            ));
+            assert!(matches!(
+                backend.delete(slot),
+                Err(SaveError::InvalidSlotName(_))
+            ));
+        }
+    }
+}
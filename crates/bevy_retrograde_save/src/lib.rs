@@ -0,0 +1,691 @@
+//! Bevy Retrograde save data plugin
+//!
+//! Resources that implement [`Serialize`] and [`Deserialize`] can be registered with
+//! [`SaveAppExt::register_save_resource`], and will then be saved to and loaded from named save
+//! slots in the [`SaveStorage`].
+//!
+//! Saved resources can be tagged with a schema version using [`SaveVersion`], so that save data
+//! from older versions of a game can be upgraded with migrations when it is loaded.
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde_save::prelude::*;
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Resource, Serialize, Deserialize, Default)]
+//! struct Progress {
+//!     level: u32,
+//!     coins: u32,
+//! }
+//!
+//! fn build(app: &mut App) {
+//!     app.add_plugins(RetroSavePlugin::new("my_game"))
+//!         .init_resource::<Progress>()
+//!         .register_save_resource::<Progress>("progress");
+//! }
+//!
+//! #[derive(Resource, Serialize, Deserialize, Default)]
+//! struct Settings {
+//!     volume: f32,
+//! }
+//!
+//! // Version 0 stored the volume as a percentage
+//! impl SaveVersion for Settings {
+//!     const SAVE_VERSION: u32 = 1;
+//! }
+//!
+//! fn build_versioned(app: &mut App) {
+//!     app.init_resource::<Settings>()
+//!         .register_versioned_save_resource::<Settings>("settings")
+//!         .add_save_migration::<Settings, _>(0, |mut value| {
+//!             let volume = value["volume"].as_f64().unwrap_or(100.0);
+//!             value["volume"] = (volume / 100.0).into();
+//!             Ok(value)
+//!         });
+//! }
+//!
+//! fn save_on_exit(mut commands: Commands) {
+//!     commands.save_slot("slot1");
+//! }
+//!
+//! fn continue_game(mut commands: Commands) {
+//!     commands.load_slot("slot1");
+//! }
+//! ```
+
+use std::{any::TypeId, collections::BTreeMap, sync::Arc};
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub mod backend;
+pub use backend::*;
+
+pub mod migration;
+pub use migration::*;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use crate::{
+        backend::*, migration::*, RetroSavePlugin, SaveAppExt, SaveCommandsExt, SaveError,
+        SaveEvent, SaveEventKind, SaveWorldExt,
+    };
+}
+
+/// Save data plugin for Bevy Retrograde
+///
+/// Inserts a [`SaveStorage`] with the default backend for the platform, unless one has already
+/// been inserted.
+pub struct RetroSavePlugin {
+    /// The name of the game, used to pick the directory or storage key that slots are saved in
+    ///
+    /// **Default:** The name of the executable
+    pub app_name: String,
+}
+
+impl RetroSavePlugin {
+    /// Save slots under the given app name
+    pub fn new(app_name: &str) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+        }
+    }
+}
+
+impl Default for RetroSavePlugin {
+    fn default() -> Self {
+        let app_name = std::env::current_exe()
+            .ok()
+            .and_then(|path| path.file_stem()?.to_str().map(String::from))
+            .unwrap_or_else(|| "bevy_retrograde".to_string());
+
+        Self { app_name }
+    }
+}
+
+impl Plugin for RetroSavePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<SaveStorage>() {
+            app.insert_resource(SaveStorage::platform_default(&self.app_name));
+        }
+
+        app.init_resource::<SaveRegistry>().add_event::<SaveEvent>();
+    }
+}
+
+/// An error that occurred while saving or loading a slot
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    #[error("IO Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization Error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Storage Error: {0}")]
+    Storage(String),
+    #[error("Invalid save slot name: {0:?}")]
+    InvalidSlotName(String),
+    #[error("Save slot not found: {0}")]
+    SlotNotFound(String),
+    #[error("No migration registered for {key:?} from version {from_version}")]
+    MissingMigration { key: String, from_version: u32 },
+    #[error("Saved {key:?} is version {version}, which is newer than version {current_version}")]
+    UnsupportedVersion {
+        key: String,
+        version: u32,
+        current_version: u32,
+    },
+    #[error("Migration Error: {0}")]
+    MigrationError(String),
+}
+
+/// The data stored in a save slot
+#[derive(Serialize, Deserialize, Default)]
+struct SlotData {
+    /// The saved resources, by their save keys
+    resources: BTreeMap<String, serde_json::Value>,
+    /// The versions of the saved resources, by their save keys. Resources that aren't in this map
+    /// are at version `0`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    versions: BTreeMap<String, u32>,
+}
+
+/// A resource type that has been registered to be saved
+#[derive(Clone)]
+struct SavedResource {
+    key: String,
+    type_id: TypeId,
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+    save: fn(&World) -> Option<Result<serde_json::Value, SaveError>>,
+    deserialize: fn(serde_json::Value) -> Result<InsertResource, SaveError>,
+}
+
+/// Function that inserts a resource that has been deserialized from a slot into the world
+type InsertResource = Box<dyn FnOnce(&mut World)>;
+
+/// The resource types that are saved to slots
+#[derive(Resource, Default)]
+struct SaveRegistry {
+    resources: Vec<SavedResource>,
+}
+
+fn save_resource<T: Resource + Serialize>(
+    world: &World,
+) -> Option<Result<serde_json::Value, SaveError>> {
+    let resource = world.get_resource::<T>()?;
+
+    Some(serde_json::to_value(resource).map_err(SaveError::from))
+}
+
+fn deserialize_resource<T: Resource + DeserializeOwned>(
+    value: serde_json::Value,
+) -> Result<InsertResource, SaveError> {
+    let resource = serde_json::from_value::<T>(value)?;
+
+    Ok(Box::new(move |world: &mut World| {
+        world.insert_resource(resource);
+    }))
+}
+
+/// Extension trait for registering resources to save on the Bevy [`App`]
+pub trait SaveAppExt {
+    /// Save the resource `T` under the given key in save slots
+    ///
+    /// The key must be unique among the saved resources, and should not be changed once the game
+    /// has been released, or old saves will not load the resource. Registering `T` again replaces
+    /// its key, and keeps the migrations that have been added for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is already used by a different resource type.
+    fn register_save_resource<T>(&mut self, key: &str) -> &mut Self
+    where
+        T: Resource + Serialize + DeserializeOwned;
+
+    /// Save the resource `T` under the given key in save slots, tagged with its [`SaveVersion`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is already used by a different resource type.
+    fn register_versioned_save_resource<T>(&mut self, key: &str) -> &mut Self
+    where
+        T: Resource + Serialize + DeserializeOwned + SaveVersion;
+
+    /// Add a migration that upgrades the save data of the resource `T` from `from_version` to
+    /// `from_version + 1`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` has not been registered as a saved resource.
+    fn add_save_migration<T, F>(&mut self, from_version: u32, migration: F) -> &mut Self
+    where
+        T: Resource,
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, SaveError> + Send + Sync + 'static;
+}
+
+impl SaveAppExt for App {
+    fn register_save_resource<T>(&mut self, key: &str) -> &mut Self
+    where
+        T: Resource + Serialize + DeserializeOwned,
+    {
+        register_save_resource::<T>(self, key, 0);
+
+        self
+    }
+
+    fn register_versioned_save_resource<T>(&mut self, key: &str) -> &mut Self
+    where
+        T: Resource + Serialize + DeserializeOwned + SaveVersion,
+    {
+        register_save_resource::<T>(self, key, T::SAVE_VERSION);
+
+        self
+    }
+
+    fn add_save_migration<T, F>(&mut self, from_version: u32, migration: F) -> &mut Self
+    where
+        T: Resource,
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, SaveError> + Send + Sync + 'static,
+    {
+        let mut registry = self
+            .world
+            .get_resource_or_insert_with(SaveRegistry::default);
+        let resource = registry
+            .resources
+            .iter_mut()
+            .find(|x| x.type_id == TypeId::of::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Cannot add a save migration for `{}` because it is not a saved resource",
+                    std::any::type_name::<T>()
+                )
+            });
+        resource
+            .migrations
+            .insert(from_version, Arc::new(migration));
+
+        self
+    }
+}
+
+fn register_save_resource<T>(app: &mut App, key: &str, version: u32)
+where
+    T: Resource + Serialize + DeserializeOwned,
+{
+    let mut registry = app.world.get_resource_or_insert_with(SaveRegistry::default);
+    if registry
+        .resources
+        .iter()
+        .any(|x| x.key == key && x.type_id != TypeId::of::<T>())
+    {
+        panic!(
+            "Cannot save `{}` under the key {:?} because another resource is saved under it",
+            std::any::type_name::<T>(),
+            key
+        );
+    }
+
+    // Keep the migrations of a resource that is registered again
+    let migrations = registry
+        .resources
+        .iter()
+        .position(|x| x.type_id == TypeId::of::<T>())
+        .map(|i| registry.resources.remove(i).migrations)
+        .unwrap_or_default();
+    registry.resources.push(SavedResource {
+        key: key.to_string(),
+        type_id: TypeId::of::<T>(),
+        version,
+        migrations,
+        save: save_resource::<T>,
+        deserialize: deserialize_resource::<T>,
+    });
+}
+
+/// Extension trait for saving and loading slots directly from the [`World`]
+pub trait SaveWorldExt {
+    /// Save the registered resources to a slot
+    ///
+    /// Registered resources that don't exist in the world are not saved.
+    fn save_slot(&self, slot: &str) -> Result<(), SaveError>;
+
+    /// Load the registered resources from a slot, inserting them into the world
+    ///
+    /// Resources saved with older versions are upgraded with their migrations first. If any
+    /// resource can't be upgraded or deserialized, no resources are loaded. Registered resources
+    /// that aren't in the slot are left unchanged.
+    fn load_slot(&mut self, slot: &str) -> Result<(), SaveError>;
+
+    /// Delete a slot
+    fn delete_slot(&self, slot: &str) -> Result<(), SaveError>;
+}
+
+impl SaveWorldExt for World {
+    fn save_slot(&self, slot: &str) -> Result<(), SaveError> {
+        let mut data = SlotData::default();
+        if let Some(registry) = self.get_resource::<SaveRegistry>() {
+            for resource in &registry.resources {
+                if let Some(value) = (resource.save)(self) {
+                    data.resources.insert(resource.key.clone(), value?);
+                    if resource.version != 0 {
+                        data.versions.insert(resource.key.clone(), resource.version);
+                    }
+                }
+            }
+        }
+
+        let data = serde_json::to_string_pretty(&data)?;
+        self.resource::<SaveStorage>().write(slot, &data)
+    }
+
+    fn load_slot(&mut self, slot: &str) -> Result<(), SaveError> {
+        let data = self
+            .resource::<SaveStorage>()
+            .read(slot)?
+            .ok_or_else(|| SaveError::SlotNotFound(slot.to_string()))?;
+        let mut data: SlotData = serde_json::from_str(&data)?;
+
+        let resources = self
+            .get_resource::<SaveRegistry>()
+            .map(|x| x.resources.clone())
+            .unwrap_or_default();
+
+        // Upgrade and deserialize every resource before inserting any, so that a missing migration
+        // or invalid data doesn't leave the slot partially loaded
+        let mut inserts = Vec::new();
+        for resource in &resources {
+            if let Some(value) = data.resources.remove(&resource.key) {
+                let saved_version = data.versions.get(&resource.key).copied().unwrap_or(0);
+                let value = migrate(
+                    &resource.key,
+                    value,
+                    saved_version,
+                    resource.version,
+                    &resource.migrations,
+                )?;
+                inserts.push((resource.deserialize)(value)?);
+            }
+        }
+
+        for insert in inserts {
+            insert(self);
+        }
+
+        Ok(())
+    }
+
+    fn delete_slot(&self, slot: &str) -> Result<(), SaveError> {
+        self.resource::<SaveStorage>().delete(slot)
+    }
+}
+
+/// Event sent after a slot has been saved, loaded, or deleted with [`SaveCommandsExt`]
+#[derive(Event, Debug)]
+pub struct SaveEvent {
+    /// The name of the slot
+    pub slot: String,
+    /// What was done to the slot
+    pub kind: SaveEventKind,
+    /// Whether or not it was successful
+    pub result: Result<(), SaveError>,
+}
+
+/// The kind of a [`SaveEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveEventKind {
+    Saved,
+    Loaded,
+    Deleted,
+}
+
+/// Extension trait for saving and loading slots with Bevy [`Commands`]
+///
+/// The result of each command is sent as a [`SaveEvent`], and errors are also logged.
+pub trait SaveCommandsExt {
+    /// Save the registered resources to a slot
+    fn save_slot(&mut self, slot: &str);
+    /// Load the registered resources from a slot
+    fn load_slot(&mut self, slot: &str);
+    /// Delete a slot
+    fn delete_slot(&mut self, slot: &str);
+}
+
+impl<'w, 's> SaveCommandsExt for Commands<'w, 's> {
+    fn save_slot(&mut self, slot: &str) {
+        let slot = slot.to_string();
+        self.add(move |world: &mut World| {
+            let result = world.save_slot(&slot);
+            send_save_event(world, slot, SaveEventKind::Saved, result);
+        });
+    }
+
+    fn load_slot(&mut self, slot: &str) {
+        let slot = slot.to_string();
+        self.add(move |world: &mut World| {
+            let result = world.load_slot(&slot);
+            send_save_event(world, slot, SaveEventKind::Loaded, result);
+        });
+    }
+
+    fn delete_slot(&mut self, slot: &str) {
+        let slot = slot.to_string();
+        self.add(move |world: &mut World| {
+            let result = world.delete_slot(&slot);
+            send_save_event(world, slot, SaveEventKind::Deleted, result);
+        });
+    }
+}
+
+fn send_save_event(
+    world: &mut World,
+    slot: String,
+    kind: SaveEventKind,
+    result: Result<(), SaveError>,
+) {
+    if let Err(e) = &result {
+        let action = match kind {
+            SaveEventKind::Saved => "save",
+            SaveEventKind::Loaded => "load",
+            SaveEventKind::Deleted => "delete",
+        };
+        error!("Could not {} save slot {:?}: {}", action, slot, e);
+    }
+
+    world.send_event(SaveEvent { slot, kind, result });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Serialize, Deserialize, Debug, Default, PartialEq)]
+    struct Progress {
+        level: u32,
+        coins: u32,
+    }
+
+    #[derive(Resource, Serialize, Deserialize, Debug, Default, PartialEq)]
+    struct Settings {
+        volume: f32,
+    }
+
+    fn app_with_backend(backend: &MemoryBackend) -> App {
+        let mut app = App::new();
+        app.insert_resource(SaveStorage::new(backend.clone()))
+            .add_plugins(RetroSavePlugin::new("test"))
+            .register_save_resource::<Progress>("progress")
+            .register_save_resource::<Settings>("settings");
+        app
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let backend = MemoryBackend::default();
+
+        let mut app = app_with_backend(&backend);
+        app.insert_resource(Progress {
+            level: 3,
+            coins: 42,
+        })
+        .insert_resource(Settings { volume: 0.5 });
+        app.world.save_slot("slot1").unwrap();
+        assert_eq!(backend.slots().unwrap(), ["slot1"]);
+
+        let mut app = app_with_backend(&backend);
+        app.world.load_slot("slot1").unwrap();
+        assert_eq!(
+            *app.world.resource::<Progress>(),
+            Progress {
+                level: 3,
+                coins: 42
+            }
+        );
+        assert_eq!(*app.world.resource::<Settings>(), Settings { volume: 0.5 });
+
+        app.world.delete_slot("slot1").unwrap();
+        assert!(matches!(
+            app.world.load_slot("slot1"),
+            Err(SaveError::SlotNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn missing_resources_are_not_saved_or_loaded() {
+        let backend = MemoryBackend::default();
+
+        let mut app = app_with_backend(&backend);
+        app.insert_resource(Progress { level: 1, coins: 0 });
+        app.world.save_slot("slot1").unwrap();
+
+        let mut app = app_with_backend(&backend);
+        app.insert_resource(Settings { volume: 0.25 });
+        app.world.load_slot("slot1").unwrap();
+        assert_eq!(
+            *app.world.resource::<Progress>(),
+            Progress { level: 1, coins: 0 }
+        );
+        assert_eq!(*app.world.resource::<Settings>(), Settings { volume: 0.25 });
+    }
+
+    #[derive(Resource, Serialize, Deserialize, Debug, Default, PartialEq)]
+    struct Options {
+        volume: f32,
+        muted: bool,
+    }
+
+    impl SaveVersion for Options {
+        const SAVE_VERSION: u32 = 2;
+    }
+
+    /// Write a slot with `Options` data at the given version, as an older game would have
+    fn write_options(backend: &MemoryBackend, version: u32, options: serde_json::Value) {
+        let mut data = SlotData::default();
+        data.resources.insert("options".into(), options);
+        if version != 0 {
+            data.versions.insert("options".into(), version);
+        }
+        backend
+            .write("slot1", &serde_json::to_string(&data).unwrap())
+            .unwrap();
+    }
+
+    /// Register `Options` with migrations from version 0, which stored the volume as a
+    /// percentage, and version 1, which had no `muted` field
+    fn register_options(app: &mut App) {
+        app.register_versioned_save_resource::<Options>("options")
+            .add_save_migration::<Options, _>(0, |mut value| {
+                let volume = value["volume"].as_f64().unwrap_or(100.0);
+                value["volume"] = (volume / 100.0).into();
+                Ok(value)
+            })
+            .add_save_migration::<Options, _>(1, |mut value| {
+                value["muted"] = false.into();
+                Ok(value)
+            });
+    }
+
+    #[test]
+    fn migrations_are_chained() {
+        let backend = MemoryBackend::default();
+        write_options(&backend, 0, serde_json::json!({ "volume": 50.0 }));
+
+        let mut app = app_with_backend(&backend);
+        register_options(&mut app);
+        app.world.load_slot("slot1").unwrap();
+        assert_eq!(
+            *app.world.resource::<Options>(),
+            Options {
+                volume: 0.5,
+                muted: false
+            }
+        );
+
+        // Saving again writes the current version, which loads without migrating
+        app.world.save_slot("slot1").unwrap();
+        let data: SlotData =
+            serde_json::from_str(&backend.read("slot1").unwrap().unwrap()).unwrap();
+        assert_eq!(data.versions["options"], 2);
+        app.world.remove_resource::<Options>();
+        app.world.load_slot("slot1").unwrap();
+        assert_eq!(app.world.resource::<Options>().volume, 0.5);
+    }
+
+    #[test]
+    fn missing_migration() {
+        let backend = MemoryBackend::default();
+        write_options(&backend, 1, serde_json::json!({ "volume": 0.5 }));
+
+        let mut app = app_with_backend(&backend);
+        app.register_versioned_save_resource::<Options>("options")
+            .add_save_migration::<Options, _>(0, Ok);
+        assert!(matches!(
+            app.world.load_slot("slot1"),
+            Err(SaveError::MissingMigration {
+                from_version: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let backend = MemoryBackend::default();
+        write_options(&backend, 3, serde_json::json!({ "volume": 0.5 }));
+
+        let mut app = app_with_backend(&backend);
+        register_options(&mut app);
+        assert!(matches!(
+            app.world.load_slot("slot1"),
+            Err(SaveError::UnsupportedVersion {
+                version: 3,
+                current_version: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn failed_loads_load_nothing() {
+        let backend = MemoryBackend::default();
+        let mut data = SlotData::default();
+        data.resources.insert(
+            "progress".into(),
+            serde_json::json!({ "level": 5, "coins": 10 }),
+        );
+        data.resources
+            .insert("settings".into(), serde_json::json!({ "volume": "loud" }));
+        backend
+            .write("slot1", &serde_json::to_string(&data).unwrap())
+            .unwrap();
+
+        let mut app = app_with_backend(&backend);
+        assert!(matches!(
+            app.world.load_slot("slot1"),
+            Err(SaveError::SerializationError(_))
+        ));
+        assert!(app.world.get_resource::<Progress>().is_none());
+        assert!(app.world.get_resource::<Settings>().is_none());
+    }
+
+    #[test]
+    fn registering_again_keeps_migrations() {
+        let backend = MemoryBackend::default();
+        write_options(&backend, 0, serde_json::json!({ "volume": 50.0 }));
+
+        let mut app = app_with_backend(&backend);
+        register_options(&mut app);
+        app.register_versioned_save_resource::<Options>("options");
+        app.world.load_slot("slot1").unwrap();
+        assert_eq!(app.world.resource::<Options>().volume, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "another resource is saved under it")]
+    fn conflicting_keys_panic() {
+        let backend = MemoryBackend::default();
+        let mut app = app_with_backend(&backend);
+        app.register_save_resource::<Options>("progress");
+    }
+
+    #[test]
+    fn save_commands_send_events() {
+        let backend = MemoryBackend::default();
+
+        let mut app = app_with_backend(&backend);
+        app.insert_resource(Progress { level: 2, coins: 7 })
+            .add_systems(Update, |mut commands: Commands| {
+                commands.save_slot("slot1");
+                commands.load_slot("missing");
+            });
+        app.update();
+
+        let events = app.world.resource::<Events<SaveEvent>>();
+        let events = events.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, SaveEventKind::Saved);
+        assert!(events[0].result.is_ok());
+        assert_eq!(events[1].kind, SaveEventKind::Loaded);
+        assert!(matches!(events[1].result, Err(SaveError::SlotNotFound(_))));
+    }
+}
@@ -0,0 +1,55 @@
+//! Save data versioning and migrations
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::SaveError;
+
+/// Trait for tagging a saved resource type with the version of its save data schema
+///
+/// Increment the version whenever a change to the type would stop old save data from
+/// deserializing, and register a migration from the previous version with
+/// [`SaveAppExt::add_save_migration`][crate::SaveAppExt::add_save_migration].
+///
+/// Resources registered with
+/// [`SaveAppExt::register_save_resource`][crate::SaveAppExt::register_save_resource] are at version
+/// `0`.
+pub trait SaveVersion {
+    /// The current version of the type's save data
+    const SAVE_VERSION: u32;
+}
+
+/// A function that upgrades saved data from one version to the next
+///
+/// Migrations operate on the [`serde_json::Value`] tree of the saved data.
+pub type Migration =
+    Arc<dyn Fn(serde_json::Value) -> Result<serde_json::Value, SaveError> + Send + Sync>;
+
+/// Upgrade saved data from its saved version to the current version by running each migration in
+/// turn
+pub(crate) fn migrate(
+    key: &str,
+    mut value: serde_json::Value,
+    saved_version: u32,
+    current_version: u32,
+    migrations: &BTreeMap<u32, Migration>,
+) -> Result<serde_json::Value, SaveError> {
+    if saved_version > current_version {
+        return Err(SaveError::UnsupportedVersion {
+            key: key.to_string(),
+            version: saved_version,
+            current_version,
+        });
+    }
+
+    for version in saved_version..current_version {
+        let migration = migrations
+            .get(&version)
+            .ok_or_else(|| SaveError::MissingMigration {
+                key: key.to_string(),
+                from_version: version,
+            })?;
+        value = migration(value)?;
+    }
+
+    Ok(value)
+}
@@ -0,0 +1,108 @@
+//! Loading fixture assets
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::{update_asset_storage_system, Asset, LoadState},
+    ecs::system::System,
+    prelude::*,
+};
+
+use crate::RetroTestApp;
+
+impl RetroTestApp {
+    /// How long to wait for a fixture to load before giving up
+    pub const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Load an asset from the fixture directory, blocking until it has been loaded
+    ///
+    /// No frames are run while waiting, so loading fixtures doesn't change the state of the game.
+    /// Only the asset itself is waited for. Any other assets that it depends on, such as the tile
+    /// set images of an LDtk map, are added to their storages when the next frame is run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the asset fails to load, or takes longer than [`Self::LOAD_TIMEOUT`].
+    #[track_caller]
+    pub fn load<T: Asset>(&mut self, path: &str) -> Handle<T> {
+        let handle = self.app.world.resource::<AssetServer>().load(path);
+        self.wait_for_asset(&handle, path);
+        handle
+    }
+
+    /// Block until an asset that is already loading has been added to its storage
+    ///
+    /// # Panics
+    ///
+    /// Panics if the asset fails to load, or takes longer than [`Self::LOAD_TIMEOUT`].
+    #[track_caller]
+    pub fn wait_for<T: Asset>(&mut self, handle: &Handle<T>) -> &mut Self {
+        let path = self
+            .app
+            .world
+            .resource::<AssetServer>()
+            .get_handle_path(handle)
+            .map(|path| path.path().display().to_string())
+            .unwrap_or_else(|| format!("{:?}", handle.id()));
+        self.wait_for_asset(handle, &path);
+        self
+    }
+
+    #[track_caller]
+    fn wait_for_asset<T: Asset>(&mut self, handle: &Handle<T>, path: &str) {
+        // Loaded assets are moved into the storage without running the rest of the frame
+        let mut update_storage = IntoSystem::into_system(update_asset_storage_system::<T>);
+        update_storage.initialize(&mut self.app.world);
+
+        let start = Instant::now();
+        loop {
+            update_storage.run((), &mut self.app.world);
+            if self.app.world.resource::<Assets<T>>().contains(handle) {
+                return;
+            }
+
+            match self
+                .app
+                .world
+                .resource::<AssetServer>()
+                .get_load_state(handle)
+            {
+                LoadState::Failed => panic!("Fixture {path:?} failed to load"),
+                LoadState::Unloaded => panic!("Fixture {path:?} isn't being loaded"),
+                _ => (),
+            }
+            if start.elapsed() > Self::LOAD_TIMEOUT {
+                panic!("Timed out waiting for fixture {path:?} to load");
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::core::FrameCount;
+
+    use super::*;
+
+    #[test]
+    fn wait_for_loads_the_asset() {
+        let mut app = RetroTestApp::new("../../assets");
+        let frame = app.world.resource::<FrameCount>().0;
+
+        let handle: Handle<Image> = app.world.resource::<AssetServer>().load("block.png");
+        app.wait_for(&handle);
+
+        let image = app.world.resource::<Assets<Image>>().get(&handle).unwrap();
+        assert!(image.size().x > 0.0);
+        // Waiting doesn't run any frames
+        assert_eq!(app.world.resource::<FrameCount>().0, frame);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to load")]
+    fn missing_fixtures_panic() {
+        let mut app = RetroTestApp::new("../../assets");
+        app.load::<Image>("missing.png");
+    }
+}
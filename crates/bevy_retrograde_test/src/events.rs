@@ -0,0 +1,162 @@
+//! Recording events to check after stepping
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+use crate::RetroTestApp;
+
+/// Resource with every event of type `E` that has been sent since the events were last cleared
+///
+/// Bevy only keeps events for two frames, so the events are copied here at the end of every frame
+/// to make sure none of them are missed when stepping many frames at once.
+#[derive(Resource, Debug, Clone)]
+pub struct RecordedEvents<E: Event> {
+    /// The recorded events, in the order that they were sent
+    pub events: Vec<E>,
+}
+
+impl<E: Event> Default for RecordedEvents<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+/// Plugin that records events of type `E` into [`RecordedEvents`]
+///
+/// [`CollisionEvent`]s are always recorded by the [`RetroTestApp`].
+pub struct RecordEventsPlugin<E>(PhantomData<E>);
+
+impl<E> Default for RecordEventsPlugin<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E: Event + Clone> Plugin for RecordEventsPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<E>()
+            .init_resource::<RecordedEvents<E>>()
+            .add_systems(Last, record_events::<E>);
+    }
+}
+
+/// System that copies events into the [`RecordedEvents`]
+fn record_events<E: Event + Clone>(
+    mut reader: EventReader<E>,
+    mut recorded: ResMut<RecordedEvents<E>>,
+) {
+    recorded.events.extend(reader.iter().cloned());
+}
+
+impl RetroTestApp {
+    /// Start recording events of type `E`
+    pub fn record_events<E: Event + Clone>(&mut self) -> &mut Self {
+        if !self.app.world.contains_resource::<RecordedEvents<E>>() {
+            self.app.add_plugins(RecordEventsPlugin::<E>::default());
+        }
+        self
+    }
+
+    /// Get the events of type `E` that have been recorded since they were last cleared
+    ///
+    /// # Panics
+    ///
+    /// Panics if the events aren't being recorded.
+    #[track_caller]
+    pub fn events<E: Event>(&self) -> &[E] {
+        &self
+            .app
+            .world
+            .get_resource::<RecordedEvents<E>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "{} events aren't being recorded, call `record_events` first",
+                    std::any::type_name::<E>()
+                )
+            })
+            .events
+    }
+
+    /// Forget the events of type `E` that have been recorded
+    pub fn clear_events<E: Event>(&mut self) -> &mut Self {
+        if let Some(mut recorded) = self.app.world.get_resource_mut::<RecordedEvents<E>>() {
+            recorded.events.clear();
+        }
+        self
+    }
+
+    /// Whether or not a collision between two entities has started since the collision events were
+    /// last cleared, in either order
+    pub fn collision_started(&self, a: Entity, b: Entity) -> bool {
+        self.has_collision(a, b, true)
+    }
+
+    /// Whether or not a collision between two entities has stopped since the collision events were
+    /// last cleared, in either order
+    pub fn collision_stopped(&self, a: Entity, b: Entity) -> bool {
+        self.has_collision(a, b, false)
+    }
+
+    fn has_collision(&self, a: Entity, b: Entity, started: bool) -> bool {
+        self.events::<CollisionEvent>().iter().any(|event| {
+            let (x, y) = event.entities();
+            event.is_started() == started && ((x, y) == (a, b) || (x, y) == (b, a))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_collision_started() {
+        let mut app = RetroTestApp::new("../../assets");
+        let floor = app
+            .world
+            .spawn((
+                TransformBundle::default(),
+                Collider::cuboid(20.0, 4.0),
+                RigidBody::Fixed,
+            ))
+            .id();
+        let ball = app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, 40.0, 0.0)),
+                Collider::ball(4.0),
+                RigidBody::Dynamic,
+                ActiveEvents::COLLISION_EVENTS,
+            ))
+            .id();
+
+        app.step(1);
+        assert!(!app.collision_started(floor, ball));
+
+        // The events are kept for longer than the two frames that Bevy keeps them for
+        app.step_until(300, |app| app.collision_started(floor, ball));
+        app.step(5);
+        assert!(app.collision_started(ball, floor));
+        assert!(!app.collision_stopped(ball, floor));
+
+        app.clear_events::<CollisionEvent>();
+        assert!(!app.collision_started(floor, ball));
+    }
+
+    #[derive(Event, Clone, Debug, PartialEq)]
+    struct Scored(u32);
+
+    #[test]
+    fn records_custom_events() {
+        let mut app = RetroTestApp::new("../../assets");
+        app.record_events::<Scored>();
+
+        app.world.send_event(Scored(1));
+        app.step(3);
+        app.world.send_event(Scored(2));
+        app.step(3);
+        assert_eq!(app.events::<Scored>(), [Scored(1), Scored(2)]);
+    }
+}
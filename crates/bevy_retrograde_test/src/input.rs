@@ -0,0 +1,188 @@
+//! Simulated input
+
+use bevy::{
+    input::{
+        gamepad::{
+            GamepadAxisChangedEvent, GamepadAxisType, GamepadButtonChangedEvent, GamepadConnection,
+            GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+        },
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+        ButtonState,
+    },
+    prelude::*,
+};
+use bevy_retrograde::prelude::*;
+
+use crate::RetroTestApp;
+
+impl RetroTestApp {
+    /// The gamepad that simulated gamepad input comes from, which is connected the first time that
+    /// it is used
+    pub const GAMEPAD: Gamepad = Gamepad { id: 0 };
+
+    /// Press a key, mouse button, or gamepad button
+    ///
+    /// Input is sent as events, the same way that it comes from the window or gamepad, so it is
+    /// seen by the game in the next frame and stays pressed until it is released.
+    pub fn press(&mut self, binding: impl Into<InputBinding>) -> &mut Self {
+        self.send_button(binding.into(), ButtonState::Pressed)
+    }
+
+    /// Release a key, mouse button, or gamepad button
+    pub fn release(&mut self, binding: impl Into<InputBinding>) -> &mut Self {
+        self.send_button(binding.into(), ButtonState::Released)
+    }
+
+    /// Press a key, mouse button, or gamepad button for one frame and then release it
+    pub fn tap(&mut self, binding: impl Into<InputBinding>) -> &mut Self {
+        let binding = binding.into();
+        self.press(binding).step(1).release(binding)
+    }
+
+    /// Press the first binding of an action in the [`InputMap`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action has no bindings.
+    #[track_caller]
+    pub fn press_action(&mut self, action: &str) -> &mut Self {
+        let binding = self.action_binding(action);
+        self.press(binding)
+    }
+
+    /// Release the first binding of an action in the [`InputMap`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the action has no bindings.
+    #[track_caller]
+    pub fn release_action(&mut self, action: &str) -> &mut Self {
+        let binding = self.action_binding(action);
+        self.release(binding)
+    }
+
+    #[track_caller]
+    fn action_binding(&self, action: &str) -> InputBinding {
+        *self
+            .app
+            .world
+            .resource::<InputMap>()
+            .action_bindings(action)
+            .first()
+            .unwrap_or_else(|| panic!("Action {action:?} has no bindings"))
+    }
+
+    /// Move a stick or trigger of the simulated gamepad
+    pub fn set_gamepad_axis(&mut self, axis_type: GamepadAxisType, value: f32) -> &mut Self {
+        self.connect_gamepad();
+        self.app
+            .world
+            .send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                Self::GAMEPAD,
+                axis_type,
+                value,
+            )));
+        self
+    }
+
+    /// Move the mouse by the given number of logical pixels
+    pub fn move_mouse(&mut self, delta: Vec2) -> &mut Self {
+        self.app.world.send_event(MouseMotion { delta });
+        self
+    }
+
+    /// Scroll the mouse wheel by the given number of lines
+    pub fn scroll_mouse(&mut self, delta: Vec2) -> &mut Self {
+        self.app.world.send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: delta.x,
+            y: delta.y,
+            window: Entity::PLACEHOLDER,
+        });
+        self
+    }
+
+    fn send_button(&mut self, binding: InputBinding, state: ButtonState) -> &mut Self {
+        match binding {
+            InputBinding::Key(key_code) => self.app.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key_code),
+                state,
+                window: Entity::PLACEHOLDER,
+            }),
+            InputBinding::Mouse(button) => self.app.world.send_event(MouseButtonInput {
+                button,
+                state,
+                window: Entity::PLACEHOLDER,
+            }),
+            InputBinding::GamepadButton(button_type) => {
+                self.connect_gamepad();
+                let value = match state {
+                    ButtonState::Pressed => 1.0,
+                    ButtonState::Released => 0.0,
+                };
+                self.app
+                    .world
+                    .send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                        Self::GAMEPAD,
+                        button_type,
+                        value,
+                    )));
+            }
+        }
+        self
+    }
+
+    fn connect_gamepad(&mut self) {
+        if self.gamepad_connected {
+            return;
+        }
+        self.gamepad_connected = true;
+        self.app
+            .world
+            .send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(
+                Self::GAMEPAD,
+                GamepadConnection::Connected(GamepadInfo {
+                    name: "Simulated Gamepad".into(),
+                }),
+            )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tap_presses_for_one_frame() {
+        let mut app = RetroTestApp::new("../../assets");
+
+        app.tap(KeyCode::Space);
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(keys.just_pressed(KeyCode::Space));
+        assert!(keys.pressed(KeyCode::Space));
+
+        app.step(1);
+        let keys = app.world.resource::<Input<KeyCode>>();
+        assert!(keys.just_released(KeyCode::Space));
+        assert!(!keys.pressed(KeyCode::Space));
+    }
+
+    #[test]
+    fn tap_gamepad_buttons() {
+        let mut app = RetroTestApp::new("../../assets");
+        let button = GamepadButton::new(RetroTestApp::GAMEPAD, GamepadButtonType::South);
+
+        app.tap(GamepadButtonType::South);
+        assert!(app
+            .world
+            .resource::<Input<GamepadButton>>()
+            .just_pressed(button));
+
+        app.step(1);
+        let buttons = app.world.resource::<Input<GamepadButton>>();
+        assert!(buttons.just_released(button));
+        assert!(!buttons.pressed(button));
+    }
+}
@@ -0,0 +1,265 @@
+//! Bevy Retrograde test harness
+//!
+//! [`RetroTestApp`] builds an [`App`] from headless [`RetroPlugins`], so that game logic can be
+//! tested with `cargo test` on machines without a window or GPU. Every frame advances the game by
+//! exactly [`RetroTestApp::FRAME_TIME`], so physics and timers behave the same on every run.
+//!
+//! The test app can load fixture assets without stepping any frames, inject keyboard, mouse and
+//! gamepad input, and record events such as rapier [`CollisionEvent`]s so that they can be checked
+//! after stepping.
+//!
+//! # Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_retrograde::prelude::*;
+//! # use bevy_retrograde_test::prelude::*;
+//! // Fixtures are loaded relative to the crate being tested
+//! let mut app = RetroTestApp::new("../../assets");
+//!
+//! let block = app.load::<Image>("block.png");
+//! let wall = app
+//!     .world
+//!     .spawn((
+//!         TransformBundle::default(),
+//!         TesselatedCollider {
+//!             texture: block.clone(),
+//!             ..default()
+//!         },
+//!         RigidBody::Fixed,
+//!     ))
+//!     .id();
+//! let ball = app
+//!     .world
+//!     .spawn((
+//!         TransformBundle::from_transform(Transform::from_xyz(0.0, 40.0, 0.0)),
+//!         Collider::ball(4.0),
+//!         RigidBody::Dynamic,
+//!         ActiveEvents::COLLISION_EVENTS,
+//!     ))
+//!     .id();
+//!
+//! app.step(1);
+//! app.assert_has::<Collider>(wall);
+//!
+//! // Wait for the ball to fall onto the wall
+//! app.step_until(300, |app| app.collision_started(wall, ball));
+//! ```
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_retrograde::prelude::*;
+
+mod assets;
+
+pub mod events;
+pub use events::*;
+
+mod input;
+
+#[doc(hidden)]
+pub mod prelude {
+    pub use crate::{RecordEventsPlugin, RecordedEvents, RetroTestApp};
+}
+
+/// A headless Bevy Retrograde [`App`] for tests
+///
+/// The test app dereferences to the [`App`], so the world and the app builder methods can be used
+/// directly on it.
+pub struct RetroTestApp {
+    /// The app being tested
+    pub app: App,
+    /// Whether the simulated gamepad has been connected yet
+    gamepad_connected: bool,
+    /// The backend of the app's [`SaveStorage`]
+    saves: MemoryBackend,
+}
+
+impl std::ops::Deref for RetroTestApp {
+    type Target = App;
+
+    fn deref(&self) -> &Self::Target {
+        &self.app
+    }
+}
+
+impl std::ops::DerefMut for RetroTestApp {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.app
+    }
+}
+
+impl RetroTestApp {
+    /// The amount of time that passes each frame
+    pub const FRAME_TIME: std::time::Duration = std::time::Duration::from_nanos(1_000_000_000 / 60);
+
+    /// Create a test app with the default [`RetroPlugins`] that loads assets from the given
+    /// fixture directory
+    ///
+    /// The fixture directory is relative to the root of the crate that is being tested.
+    pub fn new(fixture_dir: &str) -> Self {
+        Self::with_plugins(RetroPlugins::default(), fixture_dir)
+    }
+
+    /// Create a test app with the given [`RetroPlugins`] that loads assets from the given fixture
+    /// directory
+    ///
+    /// The plugins are always made [`headless`][RetroPlugins::headless], and save slots are kept
+    /// in [`memory`][Self::saves] so that they don't carry over between runs.
+    pub fn with_plugins(plugins: RetroPlugins, fixture_dir: &str) -> Self {
+        let saves = MemoryBackend::default();
+        let mut app = App::new();
+        app.insert_resource(SaveStorage::new(saves.clone()));
+        app.add_plugins(
+            RetroPlugins {
+                headless: true,
+                ..plugins
+            }
+            .set(AssetPlugin {
+                asset_folder: fixture_dir.to_string(),
+                ..default()
+            }),
+        )
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Self::FRAME_TIME));
+
+        app.add_plugins(RecordEventsPlugin::<CollisionEvent>::default());
+
+        // Finish setting up the plugins like `App::run` would
+        while !app.ready() {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        Self {
+            app,
+            gamepad_connected: false,
+            saves,
+        }
+    }
+
+    /// The in-memory backend that the app's save slots are stored in
+    pub fn saves(&self) -> &MemoryBackend {
+        &self.saves
+    }
+
+    /// Run the given number of frames
+    pub fn step(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.app.update();
+        }
+        self
+    }
+
+    /// Run frames until the condition is true, and return the number of frames that were run
+    ///
+    /// # Panics
+    ///
+    /// Panics if the condition is still false after `max_frames`.
+    #[track_caller]
+    pub fn step_until(
+        &mut self,
+        max_frames: usize,
+        mut condition: impl FnMut(&mut Self) -> bool,
+    ) -> usize {
+        for frame in 0..max_frames {
+            if condition(self) {
+                return frame;
+            }
+            self.app.update();
+        }
+        if condition(self) {
+            return max_frames;
+        }
+        panic!("Condition was still false after {max_frames} frames");
+    }
+
+    /// Get a component of an entity
+    ///
+    /// # Panics
+    ///
+    /// Panics if the entity doesn't have the component.
+    #[track_caller]
+    pub fn component<T: Component>(&self, entity: Entity) -> &T {
+        self.app.world.get::<T>(entity).unwrap_or_else(|| {
+            panic!(
+                "Entity {entity:?} doesn't have a {} component",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+
+    /// Assert that an entity has a component
+    #[track_caller]
+    pub fn assert_has<T: Component>(&self, entity: Entity) -> &Self {
+        self.component::<T>(entity);
+        self
+    }
+
+    /// Assert that an entity doesn't have a component
+    #[track_caller]
+    pub fn assert_missing<T: Component>(&self, entity: Entity) -> &Self {
+        assert!(
+            self.app.world.get::<T>(entity).is_none(),
+            "Entity {entity:?} has a {} component",
+            std::any::type_name::<T>()
+        );
+        self
+    }
+
+    /// Count the entities with a component
+    pub fn count<T: Component>(&mut self) -> usize {
+        self.app
+            .world
+            .query_filtered::<(), With<T>>()
+            .iter(&self.app.world)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::core::FrameCount;
+
+    use super::*;
+
+    #[test]
+    fn step_until_returns_the_number_of_frames() {
+        let mut app = RetroTestApp::new("../../assets");
+        let start = app.world.resource::<FrameCount>().0;
+        let frames = app.step_until(10, |app| app.world.resource::<FrameCount>().0 >= start + 3);
+        assert_eq!(frames, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Condition was still false after 5 frames")]
+    fn step_until_times_out() {
+        let mut app = RetroTestApp::new("../../assets");
+        app.step_until(5, |_| false);
+    }
+
+    #[test]
+    fn frames_take_a_fixed_time() {
+        let mut app = RetroTestApp::new("../../assets");
+        app.step(2);
+        let start = app.world.resource::<Time>().elapsed();
+        app.step(3);
+        assert_eq!(
+            app.world.resource::<Time>().elapsed() - start,
+            RetroTestApp::FRAME_TIME * 3
+        );
+    }
+
+    #[test]
+    fn saves_are_kept_in_memory() {
+        let app = RetroTestApp::new("../../assets");
+        app.world
+            .resource::<SaveStorage>()
+            .write("slot1", "{}")
+            .unwrap();
+        assert_eq!(app.saves().slots().unwrap(), ["slot1"]);
+
+        // Saves don't carry over to other apps
+        let app = RetroTestApp::new("../../assets");
+        assert!(app.saves().slots().unwrap().is_empty());
+    }
+}
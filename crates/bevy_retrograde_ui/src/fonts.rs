@@ -9,10 +9,11 @@ use bevy::{
     reflect::{TypePath, TypeUuid},
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
     utils::HashMap,
+    window::PrimaryWindow,
 };
 use bevy_egui::{
     egui::{self, mutex::Mutex},
-    EguiContexts,
+    EguiContext, EguiUserTextures,
 };
 use image::{GenericImage, Rgba, RgbaImage};
 use rectangle_pack::{
@@ -31,12 +32,23 @@ pub struct RetroFontCacheItem {
 }
 
 /// Loop through all [`RetroFont`] assets and map their texture ids and uvs to their handle
-pub(crate) fn font_texture_update(fonts: Res<Assets<RetroFont>>, mut egui_ctx: EguiContexts) {
+///
+/// Nothing is done until the primary window has an egui context, so fonts can still be loaded in
+/// headless apps.
+pub(crate) fn font_texture_update(
+    fonts: Res<Assets<RetroFont>>,
+    mut user_textures: ResMut<EguiUserTextures>,
+    mut contexts: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    let Ok(mut egui_ctx) = contexts.get_single_mut() else {
+        return;
+    };
+
     for (handle_id, font) in fonts.iter() {
-        let texture_id = egui_ctx.add_image(font.data.texture.clone_weak());
+        let texture_id = user_textures.add_image(font.data.texture.clone_weak());
         let handle = Handle::weak(handle_id);
 
-        let ctx = egui_ctx.ctx_mut();
+        let ctx = egui_ctx.get_mut();
         ctx.memory_mut(|ctx| {
             let mut retro_font_texture_datas = ctx
                 .data
@@ -31,6 +31,7 @@ pub mod retro_label;
 pub mod prelude {
     pub use crate::{
         bordered_frame::*, fonts::*, retro_button::*, retro_label::*, BorderImage, RetroEguiUiExt,
+        RetroUiScale,
     };
     pub use bevy_egui::*;
 }
@@ -53,7 +54,7 @@ impl RetroEguiUiExt for &mut egui::Ui {
 ///
 /// # Example
 ///
-/// You can easily load your border images into a resource using the [`UiBorderImage::load_from_world()`].
+/// You can easily load your border images into a resource using the [`BorderImage::load_from_world()`].
 ///
 /// ```
 /// # use bevy::prelude::*;
@@ -71,19 +72,19 @@ impl RetroEguiUiExt for &mut egui::Ui {
 ///                 world,
 ///                 "ui/panel.png",
 ///                 UVec2::new(48, 48),
-///                 Rect::all(8.0),
+///                 UiRect::all(Val::Px(8.0)),
 ///             ),
 ///             button_up_bg: BorderImage::load_from_world(
 ///                 world,
 ///                 "ui/button-up.png",
 ///                 UVec2::new(32, 16),
-///                 Rect::all(8.0),
+///                 UiRect::all(Val::Px(8.0)),
 ///             ),
 ///             button_down_bg: BorderImage::load_from_world(
 ///                 world,
 ///                 "ui/button-down.png",
 ///                 UVec2::new(32, 16),
-///                 Rect::all(8.0),
+///                 UiRect::all(Val::Px(8.0)),
 ///             ),
 ///         }
 ///     }
@@ -123,28 +124,46 @@ impl BorderImage {
     }
 }
 
+/// Resource that sets the number of physical window pixels along each side of a UI pixel
+///
+/// While this is present, the UI is scaled by it instead of by the camera's projection. Bevy
+/// Retrograde keeps it in sync with the `RetroCameraScale` of cameras that are scaled up to the
+/// window by a whole number, and only removes it when there is no such camera if it inserted it
+/// itself.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetroUiScale(pub u32);
+
 /// This system makes sure that the UI scale of Egui matches our game scale so that a pixel in egui
 /// will be the same size as a pixel in our sprites.
 fn update_ui_scale(
     mut egui_settings: ResMut<EguiSettings>,
+    ui_scale: Option<Res<RetroUiScale>>,
     windows: Query<&Window, With<bevy::window::PrimaryWindow>>,
     projection: Query<&OrthographicProjection, With<Camera>>,
 ) {
-    if let Ok(window) = windows.get_single() {
-        if let Ok(projection) = projection.get_single() {
-            match projection.scaling_mode {
-                bevy::render::camera::ScalingMode::FixedVertical(_) => {
-                    let window_height = window.height();
-                    let scale = window_height / (projection.scale * 2.0);
-                    egui_settings.scale_factor = scale as f64;
-                }
-                bevy::render::camera::ScalingMode::FixedHorizontal(_) => {
-                    let window_width = window.width();
-                    let scale = window_width / (projection.scale * 2.0);
-                    egui_settings.scale_factor = scale as f64;
-                }
-                _ => (),
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let scale = if let Some(ui_scale) = ui_scale {
+        ui_scale.0 as f64 / window.scale_factor()
+    } else if let Ok(projection) = projection.get_single() {
+        match projection.scaling_mode {
+            bevy::render::camera::ScalingMode::FixedVertical(_) => {
+                let window_height = window.height();
+                (window_height / (projection.scale * 2.0)) as f64
             }
+            bevy::render::camera::ScalingMode::FixedHorizontal(_) => {
+                let window_width = window.width();
+                (window_width / (projection.scale * 2.0)) as f64
+            }
+            _ => return,
         }
+    } else {
+        return;
+    };
+
+    if egui_settings.scale_factor != scale {
+        egui_settings.scale_factor = scale;
     }
 }
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Animation".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, (walk_radishes, log_steps))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    commands.spawn(RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)));
+
+    // Load the animation clips. Try editing `radish.anim.ron` while the example is running!
+    let clips = asset_server.load("radish.anim.ron");
+
+    let sheets = [
+        "redRadishSheet.png",
+        "blueRadishSheet.png",
+        "yellowRadishSheet.png",
+    ];
+
+    for (i, sheet) in sheets.into_iter().enumerate() {
+        // Each sheet has 3 frames for each of the 4 directions the radish can walk in
+        let texture_atlas = texture_atlases.add(TextureAtlas::from_grid(
+            asset_server.load(sheet),
+            Vec2::splat(16.0),
+            3,
+            4,
+            None,
+            None,
+        ));
+
+        commands
+            .spawn(SpriteSheetBundle {
+                texture_atlas,
+                transform: Transform::from_xyz((i as f32 - 1.0) * 40.0, 0., 0.),
+                ..Default::default()
+            })
+            .insert(RetroAnimation::new(clips.clone(), "idle"));
+    }
+}
+
+/// Play the walk animation that matches the arrow key being pressed
+fn walk_radishes(keyboard: Res<Input<KeyCode>>, mut animations: Query<&mut RetroAnimation>) {
+    let clip = if keyboard.pressed(KeyCode::Up) {
+        "walk_up"
+    } else if keyboard.pressed(KeyCode::Down) {
+        "walk_down"
+    } else if keyboard.pressed(KeyCode::Left) {
+        "walk_left"
+    } else if keyboard.pressed(KeyCode::Right) {
+        "walk_right"
+    } else {
+        "idle"
+    };
+
+    for mut animation in &mut animations {
+        animation.play(clip);
+    }
+}
+
+/// Log the events that the clips send on specific frames
+fn log_steps(mut events: EventReader<AnimationEvent>) {
+    for event in events.iter() {
+        info!(
+            "{:?} sent {:?} in {:?}",
+            event.entity, event.name, event.clip
+        );
+    }
+}
@@ -1,10 +1,6 @@
 use bevy::prelude::*;
 use bevy_retrograde::prelude::*;
 
-// Create a stage label that will be used for our game logic stage
-#[derive(SystemSet, Debug, Eq, Hash, PartialEq, Clone)]
-struct GameStage;
-
 fn main() {
     App::new()
         .add_plugins(
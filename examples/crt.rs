@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde CRT".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .insert_resource(ClearColor(Color::rgb(0.2, 0.3, 0.4)))
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_crt)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Spawn a pixel-perfect camera with the default CRT effect
+    commands.spawn((
+        RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)),
+        CrtEffect::default(),
+    ));
+
+    let radish_images = [
+        asset_server.load("redRadish.png"),
+        asset_server.load("blueRadish.png"),
+        asset_server.load("yellowRadish.png"),
+    ];
+
+    for (i, image) in radish_images.into_iter().enumerate() {
+        commands.spawn(SpriteBundle {
+            texture: image,
+            transform: Transform::from_xyz((i as f32 - 1.0) * 40.0, 0., 0.),
+            ..Default::default()
+        });
+    }
+}
+
+/// Turn the CRT effect on and off when space is pressed
+fn toggle_crt(keyboard: Res<Input<KeyCode>>, mut effects: Query<&mut CrtEffect>) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        for mut effect in &mut effects {
+            effect.enabled = !effect.enabled;
+        }
+    }
+}
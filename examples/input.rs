@@ -0,0 +1,92 @@
+use bevy::{
+    input::gamepad::{GamepadAxisType, GamepadButtonType},
+    prelude::*,
+};
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Input".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_radish, start_rebinding, log_rebinding))
+        .run();
+}
+
+#[derive(Component)]
+struct Radish;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut input_map: ResMut<InputMap>) {
+    commands.spawn(RetroCameraBundle::fixed_height(180.0));
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("redRadish.png"),
+            ..Default::default()
+        },
+        PixelPosition::new(Vec2::ZERO),
+        Radish,
+    ));
+
+    input_map
+        .bind_axis(
+            "move_x",
+            AxisBinding::buttons(KeyCode::Left, KeyCode::Right),
+        )
+        .bind_axis(
+            "move_x",
+            AxisBinding::GamepadAxis(GamepadAxisType::LeftStickX),
+        )
+        .bind_axis("move_y", AxisBinding::buttons(KeyCode::Down, KeyCode::Up))
+        .bind_axis(
+            "move_y",
+            AxisBinding::GamepadAxis(GamepadAxisType::LeftStickY),
+        )
+        .bind_action("reset", KeyCode::Space)
+        .bind_action("reset", GamepadButtonType::South);
+
+    info!("Use the arrow keys or the left stick to move, and space or the south button to reset");
+    info!("Press F1 and then another button to rebind reset, or escape to cancel");
+}
+
+fn move_radish(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    mut radishes: Query<&mut PixelPosition, With<Radish>>,
+) {
+    let movement = actions.axis_pair("move_x", "move_y").clamp_length_max(1.0);
+    for mut position in &mut radishes {
+        position.position += movement * 60.0 * time.delta_seconds();
+
+        if actions.just_pressed("reset") {
+            position.position = Vec2::ZERO;
+        }
+    }
+}
+
+fn start_rebinding(keyboard: Res<Input<KeyCode>>, mut rebinding: ResMut<InputRebinding>) {
+    if keyboard.just_pressed(KeyCode::F1) && !rebinding.is_active() {
+        rebinding.start(RebindTarget::Action("reset".into()));
+        info!("Press a button to reset the radish");
+    }
+}
+
+fn log_rebinding(mut events: EventReader<RebindFinished>, input_map: Res<InputMap>) {
+    for event in events.iter() {
+        match event.binding {
+            Some(_) => info!(
+                "Reset is now bound to {:?}",
+                input_map.action_bindings("reset")
+            ),
+            None => info!("Rebinding cancelled"),
+        }
+    }
+}
@@ -1,9 +1,6 @@
 use bevy::{asset::ChangeWatcher, prelude::*};
 use bevy_retrograde::prelude::*;
 
-#[derive(SystemSet, Debug, Clone, Hash, Eq, PartialEq)]
-struct GameStage;
-
 fn main() {
     App::new()
         .add_plugins(
@@ -30,8 +27,10 @@ fn main() {
 }
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // Spawn the camera
-    commands.spawn(RetroCameraBundle::fixed_height(200.0));
+    // Spawn the camera, and keep it from moving outside of the selected level
+    commands
+        .spawn(RetroCameraBundle::fixed_height(200.0))
+        .insert(RetroCameraLevelBounds);
 
     // Spawn the map
     let map = asset_server.load("maps/map.ldtk");
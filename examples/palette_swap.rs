@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+/// The palettes that the radishes cycle through
+const PALETTES: [&str; 3] = [
+    "palettes/radish_red.png",
+    "palettes/radish_blue.png",
+    "palettes/radish_yellow.png",
+];
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Palette Swap".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, cycle_palettes)
+        .run();
+}
+
+/// The index of the palette in [`PALETTES`] that a radish is using
+#[derive(Component)]
+struct PaletteIndex(usize);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)));
+
+    // Every radish uses the red radish image, recolored with a different palette
+    for (i, palette) in PALETTES.into_iter().enumerate() {
+        commands.spawn((
+            PaletteSwapBundle {
+                palette_swap: PaletteSwap {
+                    texture: asset_server.load("redRadish.png"),
+                    source_palette: asset_server.load(PALETTES[0]),
+                    target_palette: asset_server.load(palette),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz((i as f32 - 1.0) * 40.0, 0., 0.),
+                ..Default::default()
+            },
+            PaletteIndex(i),
+        ));
+    }
+}
+
+/// Switch every radish to its next palette when space is pressed
+fn cycle_palettes(
+    keyboard: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut radishes: Query<(&mut PaletteSwap, &mut PaletteIndex)>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for (mut palette_swap, mut index) in &mut radishes {
+        index.0 = (index.0 + 1) % PALETTES.len();
+        palette_swap.target_palette = asset_server.load(PALETTES[index.0]);
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Parallax".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, move_camera)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)));
+
+    // Strips of blocks that repeat forever, with the further ones moving slower
+    let block = asset_server.load("block.png");
+    for (i, factor) in [0.25, 0.5, 1.0].into_iter().enumerate() {
+        commands.spawn((
+            SpriteBundle {
+                texture: block.clone(),
+                sprite: Sprite {
+                    color: Color::rgb(factor, factor, factor),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, i as f32),
+                ..Default::default()
+            },
+            ParallaxLayer {
+                origin: Vec2::new(0.0, 24.0 - i as f32 * 24.0),
+                repeat: BVec2::new(true, false),
+                ..ParallaxLayer::new(Vec2::splat(factor))
+            },
+        ));
+    }
+
+    // A radish that looks closer than the rest of the world, so it moves faster
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("redRadish.png"),
+            transform: Transform::from_xyz(0.0, 0.0, 10.0),
+            ..Default::default()
+        },
+        ParallaxLayer {
+            origin: Vec2::new(0.0, -36.0),
+            ..ParallaxLayer::new(Vec2::splat(1.5))
+        },
+    ));
+
+    info!("Use the arrow keys to move the camera");
+}
+
+fn move_camera(
+    time: Res<Time>,
+    keyboard: Res<Input<KeyCode>>,
+    mut cameras: Query<&mut Transform, With<RetroCamera>>,
+) {
+    let mut direction = Vec2::ZERO;
+    if keyboard.pressed(KeyCode::Left) {
+        direction.x -= 1.0;
+    }
+    if keyboard.pressed(KeyCode::Right) {
+        direction.x += 1.0;
+    }
+    if keyboard.pressed(KeyCode::Up) {
+        direction.y += 1.0;
+    }
+    if keyboard.pressed(KeyCode::Down) {
+        direction.y -= 1.0;
+    }
+
+    for mut transform in &mut cameras {
+        transform.translation += (direction * 40.0 * time.delta_seconds()).extend(0.0);
+    }
+}
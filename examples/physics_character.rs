@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{input::gamepad::GamepadAxisType, prelude::*};
 use bevy_retrograde::prelude::*;
 
 fn main() {
@@ -14,7 +14,7 @@ fn main() {
                 })
                 .set(ImagePlugin::default_nearest()),
         )
-        .add_systems(Startup, setup)
+        .add_systems(Startup, (setup, setup_input))
         .add_systems(Update, move_player)
         .insert_resource(RapierConfiguration {
             gravity: Vec2::ZERO,
@@ -26,14 +26,22 @@ fn main() {
 #[derive(Component)]
 struct Player;
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // Spawn the camera
-    commands.spawn(RetroCameraBundle::fixed_height(300.0));
-
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
     // Load our images
     let block = asset_server.load("block.png");
     let triangle = asset_server.load("triangle.png");
-    let red_radish = asset_server.load("redRadish.png");
+    let red_radish = texture_atlases.add(TextureAtlas::from_grid(
+        asset_server.load("redRadishSheet.png"),
+        Vec2::splat(16.0),
+        3,
+        4,
+        None,
+        None,
+    ));
 
     // Spawn a collider block that will just sit there and be an obstacle
     commands
@@ -105,14 +113,21 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         });
 
     // Spawn the player
-    commands
-        .spawn(SpriteBundle {
-            texture: red_radish.clone(),
+    let player = commands
+        .spawn(SpriteSheetBundle {
+            texture_atlas: red_radish,
             transform: Transform::from_xyz(0., 50., 0.),
             ..Default::default()
         })
+        // The player walks around using the animations from the animation example
+        .insert(RetroAnimation::new(
+            asset_server.load("radish.anim.ron"),
+            "idle",
+        ))
         .insert(TesselatedCollider {
-            texture: red_radish.clone(),
+            // Generate the collision shape from the frame of the sprite sheet that is being shown,
+            // so that it changes with the animation
+            region: TesselatedColliderRegion::AtlasSprite,
             tesselator_config: TesselatedColliderConfig {
                 // We want the collision shape for the player to be highly accurate
                 vertice_separation: 0.,
@@ -125,40 +140,64 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         .insert(LockedAxes::ROTATION_LOCKED)
         // Set the player speed to 0 initially
         .insert(Velocity::linear(Vec2::default()))
-        .insert(Player);
+        .insert(Player)
+        .id();
+
+    // Spawn the camera, and have it follow the player around, only moving when the player gets
+    // close to the edge of a dead zone in the middle of the screen.
+    commands
+        .spawn(RetroCameraBundle::fixed_height(300.0))
+        .insert(RetroCameraFollow {
+            dead_zone: Vec2::new(120.0, 80.0),
+            ..RetroCameraFollow::new(player)
+        });
+}
+
+/// Binds the movement axes to the keyboard arrow keys and the left gamepad stick
+fn setup_input(mut input_map: ResMut<InputMap>) {
+    input_map
+        .bind_axis(
+            "move_x",
+            AxisBinding::buttons(KeyCode::Left, KeyCode::Right),
+        )
+        .bind_axis(
+            "move_x",
+            AxisBinding::GamepadAxis(GamepadAxisType::LeftStickX),
+        )
+        .bind_axis("move_y", AxisBinding::buttons(KeyCode::Down, KeyCode::Up))
+        .bind_axis(
+            "move_y",
+            AxisBinding::GamepadAxis(GamepadAxisType::LeftStickY),
+        );
 }
 
-/// Set's the player speed based on input from the keyboard arrow keys
+/// Set's the player speed based on the movement axes
 fn move_player(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<&mut Velocity, With<Player>>,
+    actions: Res<ActionState>,
+    mut query: Query<(&mut Velocity, &mut RetroAnimation), With<Player>>,
     time: Res<Time>,
 ) {
-    for mut velocity in query.iter_mut() {
+    for (mut velocity, mut animation) in query.iter_mut() {
         let speed: f32 = 10.0 * time.delta().as_millis() as f32;
 
-        let mut direction = Vec2::new(0., 0.);
-
-        if keyboard_input.pressed(KeyCode::Left) {
-            direction += Vec2::new(-1.0, 0.);
-        }
-
-        if keyboard_input.pressed(KeyCode::Right) {
-            direction += Vec2::new(1.0, 0.);
-        }
-
-        if keyboard_input.pressed(KeyCode::Up) {
-            direction += Vec2::new(0., 1.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::Down) {
-            direction += Vec2::new(0., -1.0);
-        }
-
-        if direction.length() != 0.0 {
-            direction = direction.normalize() * speed;
-        }
-
-        *velocity = Velocity::linear(direction);
+        // Keep diagonal movement from being faster than moving straight
+        let direction = actions.axis_pair("move_x", "move_y").clamp_length_max(1.0);
+
+        *velocity = Velocity::linear(direction * speed);
+
+        // Play the walk animation for the direction that we are mostly moving in
+        animation.play(if direction == Vec2::ZERO {
+            "idle"
+        } else if direction.x.abs() > direction.y.abs() {
+            if direction.x > 0.0 {
+                "walk_right"
+            } else {
+                "walk_left"
+            }
+        } else if direction.y > 0.0 {
+            "walk_up"
+        } else {
+            "walk_down"
+        });
     }
 }
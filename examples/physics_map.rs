@@ -50,8 +50,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 
     // Spawn bouncy radishes
     for y in 0..=2 {
-        for x in -10..=10 {
-            let sprite_image = radish_images[((x as i32).abs() % 3) as usize].clone();
+        for x in -10i32..=10 {
+            let sprite_image = radish_images[(x.abs() % 3) as usize].clone();
             commands
                 .spawn(SpriteBundle {
                     texture: sprite_image.clone(),
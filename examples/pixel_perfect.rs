@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Pixel Perfect".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, spin_radishes)
+        .run();
+}
+
+#[derive(Component)]
+struct Spin;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // Spawn a camera that renders the game at 160x90 pixels and scales that up to fit the window.
+    // Even though we rotate the radishes, they will stay on the game's pixel grid.
+    commands.spawn(RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)));
+
+    let radish_images = [
+        asset_server.load("redRadish.png"),
+        asset_server.load("blueRadish.png"),
+        asset_server.load("yellowRadish.png"),
+    ];
+
+    for (i, image) in radish_images.into_iter().enumerate() {
+        commands
+            .spawn(SpriteBundle {
+                texture: image,
+                transform: Transform::from_xyz((i as f32 - 1.0) * 40.0, 0., 0.),
+                ..Default::default()
+            })
+            .insert(Spin);
+    }
+}
+
+fn spin_radishes(mut query: Query<&mut Transform, With<Spin>>, time: Res<Time>) {
+    for mut transform in query.iter_mut() {
+        transform.rotate_z(time.delta_seconds());
+    }
+}
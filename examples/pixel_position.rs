@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Pixel Position".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_snapped_radish, move_smooth_radish))
+        .run();
+}
+
+/// Marker for the radish that is moved with its transform
+#[derive(Component)]
+struct Smooth;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(RetroCameraBundle::integer_scaled(UVec2::new(160, 90)));
+
+    // This radish is moved with a `PixelPosition`, so it is always drawn on whole pixels
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("redRadish.png"),
+            ..Default::default()
+        },
+        PixelPosition::new(Vec2::new(-60.0, 12.0)),
+    ));
+
+    // This radish is moved with its `Transform`, so it is drawn between pixels and jitters
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("blueRadish.png"),
+            transform: Transform::from_xyz(-60.0, -12.0, 0.0),
+            ..Default::default()
+        },
+        Smooth,
+    ));
+}
+
+/// The speed that the radishes move at, in pixels per second
+const SPEED: f32 = 7.3;
+
+fn move_snapped_radish(time: Res<Time>, mut positions: Query<&mut PixelPosition>) {
+    for mut position in &mut positions {
+        position.position.x =
+            (position.position.x + SPEED * time.delta_seconds() + 60.0) % 120.0 - 60.0;
+    }
+}
+
+fn move_smooth_radish(time: Res<Time>, mut transforms: Query<&mut Transform, With<Smooth>>) {
+    for mut transform in &mut transforms {
+        transform.translation.x =
+            (transform.translation.x + SPEED * time.delta_seconds() + 60.0) % 120.0 - 60.0;
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_retrograde::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            RetroPlugins::default()
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Bevy Retrograde Transitions".into(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .set(ImagePlugin::default_nearest()),
+        )
+        .add_systems(Startup, setup)
+        .add_systems(Update, (start_transitions, swap_radish))
+        .run();
+}
+
+/// The radish images that are swapped between during transitions
+#[derive(Resource)]
+struct RadishImages {
+    images: Vec<Handle<Image>>,
+    current: usize,
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)));
+
+    let images = vec![
+        asset_server.load("redRadish.png"),
+        asset_server.load("blueRadish.png"),
+        asset_server.load("yellowRadish.png"),
+    ];
+    commands.spawn(SpriteBundle {
+        texture: images[0].clone(),
+        ..Default::default()
+    });
+    commands.insert_resource(RadishImages { images, current: 0 });
+
+    info!("Press 1 to fade, 2 to wipe, 3 to iris, or 4 to pixelate");
+}
+
+/// Start a transition when a number key is pressed
+fn start_transitions(keyboard: Res<Input<KeyCode>>, mut transitions: EventWriter<StartTransition>) {
+    let kind = if keyboard.just_pressed(KeyCode::Key1) {
+        TransitionKind::Fade
+    } else if keyboard.just_pressed(KeyCode::Key2) {
+        TransitionKind::Wipe {
+            direction: WipeDirection::Right,
+        }
+    } else if keyboard.just_pressed(KeyCode::Key3) {
+        TransitionKind::Iris {
+            center: Vec2::splat(0.5),
+        }
+    } else if keyboard.just_pressed(KeyCode::Key4) {
+        TransitionKind::Pixelate
+    } else {
+        return;
+    };
+
+    transitions.send(StartTransition {
+        kind,
+        duration: 0.75,
+        hold: 0.25,
+        ..Default::default()
+    });
+}
+
+/// Change the radish while the screen is covered
+fn swap_radish(
+    mut midpoints: EventReader<TransitionMidpoint>,
+    mut radish_images: ResMut<RadishImages>,
+    mut sprites: Query<&mut Handle<Image>, With<Sprite>>,
+) {
+    for _ in midpoints.iter() {
+        radish_images.current = (radish_images.current + 1) % radish_images.images.len();
+        for mut image in &mut sprites {
+            *image = radish_images.images[radish_images.current].clone();
+        }
+    }
+}
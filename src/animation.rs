@@ -0,0 +1,404 @@
+//! Sprite sheet animation
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::{Deserialize, Serialize};
+
+/// Plugin that plays [`RetroAnimation`]s on [`TextureAtlasSprite`]s
+///
+/// This is added by the [`RetroPlugins`][crate::RetroPlugins].
+pub struct RetroAnimationPlugin;
+
+impl Plugin for RetroAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AnimationClips>()
+            .add_asset_loader(AnimationClipsLoader)
+            .add_event::<AnimationEvent>()
+            .add_systems(Update, animate_sprites.in_set(RetroAnimationSet));
+
+        #[cfg(feature = "aseprite")]
+        app.add_asset::<crate::Aseprite>()
+            .add_asset_loader(crate::AsepriteLoader)
+            .add_asset_loader(crate::AsepriteJsonLoader);
+    }
+}
+
+/// The system set that [`RetroAnimation`]s are updated in
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetroAnimationSet;
+
+/// A set of named animation clips for a sprite sheet
+///
+/// Clips can be loaded from `.anim.ron` files, which are reloaded when they change if asset hot
+/// reloading is enabled.
+///
+/// # Example
+///
+/// ```ron
+/// (
+///     clips: {
+///         "idle": (start: 0, end: 0),
+///         "walk": (
+///             start: 1,
+///             end: 2,
+///             durations: [0.15],
+///             mode: PingPong,
+///             events: { 1: "footstep" },
+///         ),
+///     },
+/// )
+/// ```
+#[derive(Serialize, Deserialize, TypeUuid, TypePath, Debug, Clone, Default)]
+#[uuid = "03fc1a46-d6fa-4ced-ac61-4a0bd6ce5a3c"]
+pub struct AnimationClips {
+    /// The animation clips, by name
+    pub clips: HashMap<String, AnimationClip>,
+}
+
+impl AnimationClips {
+    /// Get a clip by name
+    pub fn get(&self, clip: &str) -> Option<&AnimationClip> {
+        self.clips.get(clip)
+    }
+}
+
+/// A range of frames in a texture atlas that are played as an animation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnimationClip {
+    /// The texture atlas index of the first frame of the clip
+    pub start: usize,
+    /// The texture atlas index of the last frame of the clip. If this is less than `start`, the
+    /// frames are played in reverse.
+    pub end: usize,
+    /// The duration of each frame in seconds. If there are fewer durations than frames, the last
+    /// duration is used for the rest of the frames.
+    ///
+    /// **Default:** `[0.1]`
+    #[serde(default = "default_durations")]
+    pub durations: Vec<f32>,
+    /// How the clip repeats
+    ///
+    /// **Default:** [`AnimationMode::Loop`]
+    #[serde(default)]
+    pub mode: AnimationMode,
+    /// The names of [`AnimationEvent`]s to send when frames are reached, by their frame number in
+    /// the clip, starting at `0`
+    #[serde(default)]
+    pub events: HashMap<usize, String>,
+}
+
+fn default_durations() -> Vec<f32> {
+    vec![0.1]
+}
+
+impl AnimationClip {
+    /// Create a clip that plays the frames from `start` to `end`, inclusive, with the same duration
+    /// for every frame
+    pub fn new(start: usize, end: usize, frame_duration: f32, mode: AnimationMode) -> Self {
+        Self {
+            start,
+            end,
+            durations: vec![frame_duration],
+            mode,
+            events: default(),
+        }
+    }
+
+    /// Get the number of frames in the clip
+    pub fn len(&self) -> usize {
+        self.start.abs_diff(self.end) + 1
+    }
+
+    /// Clips always have at least one frame
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Get the texture atlas index of a frame in the clip
+    pub fn atlas_index(&self, frame: usize) -> usize {
+        if self.end >= self.start {
+            self.start + frame
+        } else {
+            self.start - frame
+        }
+    }
+
+    /// Get the duration of a frame in the clip, in seconds
+    pub fn duration(&self, frame: usize) -> f32 {
+        self.durations
+            .get(frame)
+            .or_else(|| self.durations.last())
+            .copied()
+            .unwrap_or(0.1)
+    }
+}
+
+/// How an [`AnimationClip`] repeats once it reaches its last frame
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Start again from the first frame
+    #[default]
+    Loop,
+    /// Play backwards to the first frame, then forwards again
+    PingPong,
+    /// Stop on the last frame
+    Once,
+}
+
+/// Component that plays an [`AnimationClip`] on the entity's [`TextureAtlasSprite`]
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+/// ) {
+///     let texture_atlas = texture_atlases.add(TextureAtlas::from_grid(
+///         asset_server.load("redRadishSheet.png"),
+///         Vec2::splat(16.0),
+///         3,
+///         4,
+///         None,
+///         None,
+///     ));
+///
+///     commands
+///         .spawn(SpriteSheetBundle {
+///             texture_atlas,
+///             ..default()
+///         })
+///         .insert(RetroAnimation::new(
+///             asset_server.load("radish.anim.ron"),
+///             "walk_down",
+///         ));
+/// }
+///
+/// fn walk_up(mut animations: Query<&mut RetroAnimation>) {
+///     for mut animation in &mut animations {
+///         animation.play("walk_up");
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct RetroAnimation {
+    /// The clips that the animation plays from
+    pub clips: Handle<AnimationClips>,
+    /// The playback speed multiplier
+    ///
+    /// **Default:** `1.0`
+    pub speed: f32,
+    /// Whether or not the animation is playing
+    ///
+    /// **Default:** `true`
+    pub playing: bool,
+    clip: String,
+    frame: usize,
+    elapsed: f32,
+    backwards: bool,
+    finished: bool,
+    /// Whether the current frame has been shown on the sprite yet
+    started: bool,
+}
+
+impl RetroAnimation {
+    /// Play the given clip from a set of clips
+    pub fn new(clips: Handle<AnimationClips>, clip: &str) -> Self {
+        Self {
+            clips,
+            speed: 1.0,
+            playing: true,
+            clip: clip.to_string(),
+            frame: 0,
+            elapsed: 0.0,
+            backwards: false,
+            finished: false,
+            started: false,
+        }
+    }
+
+    /// Play a clip from its first frame, unless it is already the current clip
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.restart(clip);
+        }
+        self.playing = true;
+    }
+
+    /// Play a clip from its first frame, even if it is already the current clip
+    pub fn restart(&mut self, clip: &str) {
+        self.clip = clip.to_string();
+        self.frame = 0;
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.finished = false;
+        self.started = false;
+        self.playing = true;
+    }
+
+    /// Get the name of the current clip
+    pub fn clip(&self) -> &str {
+        &self.clip
+    }
+
+    /// Get the current frame number in the clip, starting at `0`
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Whether or not an [`AnimationMode::Once`] clip has reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Move to the next frame of the clip
+    fn advance(&mut self, clip: &AnimationClip) {
+        let last = clip.len() - 1;
+
+        match clip.mode {
+            AnimationMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            AnimationMode::Once => {
+                if self.frame >= last {
+                    self.finished = true;
+                } else {
+                    self.frame += 1;
+                }
+            }
+            AnimationMode::PingPong => {
+                if last == 0 {
+                    return;
+                }
+                if self.backwards && self.frame == 0 {
+                    self.backwards = false;
+                } else if !self.backwards && self.frame >= last {
+                    self.backwards = true;
+                }
+                if self.backwards {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Event sent when a [`RetroAnimation`] reaches a frame that has an event in its [`AnimationClip`]
+#[derive(Event, Debug, Clone)]
+pub struct AnimationEvent {
+    /// The animated entity
+    pub entity: Entity,
+    /// The name of the clip
+    pub clip: String,
+    /// The frame number in the clip
+    pub frame: usize,
+    /// The name of the event
+    pub name: String,
+}
+
+/// System that advances [`RetroAnimation`]s and updates their sprites
+fn animate_sprites(
+    time: Res<Time>,
+    clips: Res<Assets<AnimationClips>>,
+    mut events: EventWriter<AnimationEvent>,
+    mut animations: Query<(Entity, &mut RetroAnimation, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut animation, mut sprite) in &mut animations {
+        let Some(clip) = clips
+            .get(&animation.clips)
+            .and_then(|clips| clips.get(&animation.clip))
+        else {
+            continue;
+        };
+
+        // The clip may have gotten shorter if it was hot reloaded
+        if animation.frame >= clip.len() {
+            animation.frame = 0;
+            animation.started = false;
+        }
+
+        let mut frame_changed = !animation.started;
+        if !animation.started {
+            animation.started = true;
+            send_frame_event(&mut events, entity, &animation, clip);
+        }
+
+        if animation.playing && !animation.finished {
+            animation.elapsed += time.delta_seconds() * animation.speed;
+
+            while !animation.finished {
+                let duration = clip.duration(animation.frame);
+                if animation.elapsed < duration || duration <= 0.0 {
+                    break;
+                }
+                animation.elapsed -= duration;
+
+                let frame = animation.frame;
+                animation.advance(clip);
+                if animation.frame != frame {
+                    frame_changed = true;
+                    send_frame_event(&mut events, entity, &animation, clip);
+                }
+            }
+        }
+
+        if frame_changed {
+            let index = clip.atlas_index(animation.frame);
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+    }
+}
+
+fn send_frame_event(
+    events: &mut EventWriter<AnimationEvent>,
+    entity: Entity,
+    animation: &RetroAnimation,
+    clip: &AnimationClip,
+) {
+    if let Some(name) = clip.events.get(&animation.frame) {
+        events.send(AnimationEvent {
+            entity,
+            clip: animation.clip.clone(),
+            frame: animation.frame,
+            name: name.clone(),
+        });
+    }
+}
+
+/// Loader for [`AnimationClips`] from `.anim.ron` files
+#[derive(Default)]
+pub struct AnimationClipsLoader;
+
+impl AssetLoader for AnimationClipsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let clips: AnimationClips = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(clips));
+
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
@@ -0,0 +1,479 @@
+//! Aseprite file and JSON sprite sheet loaders
+
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    utils::{BoxedFuture, HashMap},
+};
+use image::{GenericImage, RgbaImage};
+use serde::{de, Deserialize, Deserializer};
+
+use crate::{AnimationClip, AnimationClips, AnimationMode};
+
+/// A sprite loaded from an Aseprite file or an Aseprite JSON sprite sheet export
+///
+/// Aseprite files are loaded from `.aseprite` and `.ase` files, and JSON exports are loaded from
+/// `.aseprite.json` files, with the sheet image next to them.
+///
+/// Each Aseprite tag becomes an [`AnimationClip`] in the [`clips`][Self::clips], using the frame
+/// durations and animation direction from Aseprite. If the sprite doesn't have any tags, a
+/// `"default"` clip is added that loops every frame.
+///
+/// The atlas and clips of an Aseprite file can also be loaded directly with the `atlas` and `clips`
+/// labels, such as `player.aseprite#clips`.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// #[derive(Resource)]
+/// struct PlayerSprite(Handle<Aseprite>);
+///
+/// fn spawn_player(
+///     mut commands: Commands,
+///     player_sprite: Res<PlayerSprite>,
+///     aseprites: Res<Assets<Aseprite>>,
+/// ) {
+///     let Some(aseprite) = aseprites.get(&player_sprite.0) else {
+///         return;
+///     };
+///
+///     commands
+///         .spawn(SpriteSheetBundle {
+///             texture_atlas: aseprite.atlas.clone(),
+///             ..default()
+///         })
+///         .insert(RetroAnimation::new(aseprite.clips.clone(), "idle"));
+/// }
+/// ```
+#[derive(TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "d2849c76-f382-42c9-ae7c-c7aeb4b5a760"]
+pub struct Aseprite {
+    /// The sprite sheet image with every frame in it
+    pub texture: Handle<Image>,
+    /// The texture atlas with one texture for each frame, in frame order
+    pub atlas: Handle<TextureAtlas>,
+    /// The animation clips made from the Aseprite tags
+    pub clips: Handle<AnimationClips>,
+    /// The Aseprite slices, by name
+    pub slices: HashMap<String, AsepriteSlice>,
+}
+
+/// A named region of an [`Aseprite`] sprite, which may change between frames
+#[derive(Debug, Clone, Default)]
+pub struct AsepriteSlice {
+    /// The keys of the slice, sorted by the frame they start at
+    pub keys: Vec<AsepriteSliceKey>,
+}
+
+impl AsepriteSlice {
+    /// Get the slice key that is active on the given frame
+    pub fn key(&self, frame: usize) -> Option<&AsepriteSliceKey> {
+        self.keys.iter().rev().find(|key| key.frame <= frame)
+    }
+}
+
+/// The bounds of an [`AsepriteSlice`] starting from a frame
+///
+/// Positions are in pixels, relative to the top-left corner of the frame, with the Y axis pointing
+/// down like it does in Aseprite.
+#[derive(Debug, Clone)]
+pub struct AsepriteSliceKey {
+    /// The frame that this key starts at
+    pub frame: usize,
+    /// The bounds of the slice
+    pub bounds: Rect,
+    /// The center of a 9-patch slice, relative to the slice bounds
+    pub center: Option<Rect>,
+    /// The pivot point of the slice, relative to the slice bounds
+    pub pivot: Option<Vec2>,
+}
+
+/// A frame of an Aseprite sprite in the sprite sheet
+struct SheetFrame {
+    rect: Rect,
+    /// The duration of the frame in seconds
+    duration: f32,
+}
+
+/// The data needed to create an [`Aseprite`] asset
+struct SpriteData {
+    texture: Handle<Image>,
+    size: Vec2,
+    frames: Vec<SheetFrame>,
+    clips: AnimationClips,
+    slices: HashMap<String, AsepriteSlice>,
+}
+
+/// Create an [`AnimationClip`] from an Aseprite tag
+fn tag_clip(
+    frames: &[SheetFrame],
+    from: usize,
+    to: usize,
+    direction: &str,
+    repeat: Option<u32>,
+) -> AnimationClip {
+    let (start, end) = match direction {
+        "reverse" | "pingpong_reverse" => (to, from),
+        _ => (from, to),
+    };
+    let mode = match (direction, repeat) {
+        ("pingpong" | "pingpong_reverse", _) => AnimationMode::PingPong,
+        (_, Some(1)) => AnimationMode::Once,
+        _ => AnimationMode::Loop,
+    };
+
+    let mut clip = AnimationClip::new(start, end, 0.1, mode);
+    clip.durations = (0..clip.len())
+        .map(|frame| {
+            frames
+                .get(clip.atlas_index(frame))
+                .map(|x| x.duration)
+                .unwrap_or(0.1)
+        })
+        .collect();
+
+    clip
+}
+
+/// Add the texture atlas and animation clips as labeled assets, and the [`Aseprite`] as the
+/// default asset
+fn set_aseprite_asset(
+    load_context: &mut LoadContext,
+    data: SpriteData,
+    dependencies: Vec<AssetPath<'static>>,
+) {
+    let mut atlas = TextureAtlas::new_empty(data.texture.clone(), data.size);
+    for frame in &data.frames {
+        atlas.add_texture(frame.rect);
+    }
+    let atlas = load_context.set_labeled_asset("atlas", LoadedAsset::new(atlas));
+
+    let mut clips = data.clips;
+    if clips.clips.is_empty() && !data.frames.is_empty() {
+        clips.clips.insert(
+            "default".into(),
+            tag_clip(&data.frames, 0, data.frames.len() - 1, "forward", None),
+        );
+    }
+    let clips = load_context.set_labeled_asset("clips", LoadedAsset::new(clips));
+
+    load_context.set_default_asset(
+        LoadedAsset::new(Aseprite {
+            texture: data.texture,
+            atlas,
+            clips,
+            slices: data.slices,
+        })
+        .with_dependencies(dependencies),
+    );
+}
+
+/// Loader for [`Aseprite`] sprites from `.aseprite` and `.ase` files
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+impl AssetLoader for AsepriteLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let ase = asefile::AsepriteFile::read(bytes)?;
+            let (width, height) = (ase.width() as u32, ase.height() as u32);
+            let frame_count = ase.num_frames();
+
+            // Lay the frames out in a grid that is roughly square
+            let columns = (frame_count as f32).sqrt().ceil().max(1.0) as u32;
+            let rows = frame_count.div_ceil(columns).max(1);
+            let mut sheet = RgbaImage::new(width * columns, height * rows);
+
+            let mut frames = Vec::with_capacity(frame_count as usize);
+            for i in 0..frame_count {
+                let frame = ase.frame(i);
+                let (x, y) = ((i % columns) * width, (i / columns) * height);
+                sheet.copy_from(&frame.image(), x, y)?;
+
+                frames.push(SheetFrame {
+                    rect: Rect::new(x as f32, y as f32, (x + width) as f32, (y + height) as f32),
+                    duration: frame.duration() as f32 / 1000.0,
+                });
+            }
+
+            let image = Image::new(
+                Extent3d {
+                    width: sheet.width(),
+                    height: sheet.height(),
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                sheet.into_raw(),
+                TextureFormat::Rgba8UnormSrgb,
+            );
+            let texture = load_context.set_labeled_asset("texture", LoadedAsset::new(image));
+
+            let mut clips = AnimationClips::default();
+            for i in 0..ase.num_tags() {
+                let tag = ase.tag(i);
+                let direction = match tag.animation_direction() {
+                    asefile::AnimationDirection::Forward => "forward",
+                    asefile::AnimationDirection::Reverse => "reverse",
+                    asefile::AnimationDirection::PingPong => "pingpong",
+                };
+                clips.clips.insert(
+                    tag.name().to_string(),
+                    tag_clip(
+                        &frames,
+                        tag.from_frame() as usize,
+                        tag.to_frame() as usize,
+                        direction,
+                        tag.repeat().map(|x| x.get()),
+                    ),
+                );
+            }
+
+            let mut slices = HashMap::<String, AsepriteSlice>::default();
+            for slice in ase.slices() {
+                let keys = &mut slices.entry(slice.name.clone()).or_default().keys;
+                for key in &slice.keys {
+                    let min = Vec2::new(key.origin.0 as f32, key.origin.1 as f32);
+                    let size = Vec2::new(key.size.0 as f32, key.size.1 as f32);
+                    keys.push(AsepriteSliceKey {
+                        frame: key.from_frame as usize,
+                        bounds: Rect::from_corners(min, min + size),
+                        center: key.slice9.as_ref().map(|center| {
+                            let min = Vec2::new(center.center_x as f32, center.center_y as f32);
+                            let size =
+                                Vec2::new(center.center_width as f32, center.center_height as f32);
+                            Rect::from_corners(min, min + size)
+                        }),
+                        pivot: key.pivot.map(|(x, y)| Vec2::new(x as f32, y as f32)),
+                    });
+                }
+                keys.sort_by_key(|key| key.frame);
+            }
+
+            set_aseprite_asset(
+                load_context,
+                SpriteData {
+                    texture,
+                    size: Vec2::new((width * columns) as f32, (height * rows) as f32),
+                    frames,
+                    clips,
+                    slices,
+                },
+                Vec::new(),
+            );
+
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite", "ase"]
+    }
+}
+
+/// A rectangle in an Aseprite JSON export
+#[derive(Deserialize)]
+struct JsonRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+impl From<&JsonRect> for Rect {
+    fn from(rect: &JsonRect) -> Self {
+        Rect::new(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h)
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonSize {
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct JsonFrame {
+    frame: JsonRect,
+    /// The frame duration in milliseconds
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSheet {
+    /// The frames, which may be exported as an array or as a map from their file names
+    #[serde(deserialize_with = "deserialize_frames")]
+    frames: Vec<JsonFrame>,
+    meta: JsonMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonMeta {
+    image: String,
+    size: JsonSize,
+    #[serde(default)]
+    frame_tags: Vec<JsonTag>,
+    #[serde(default)]
+    slices: Vec<JsonSlice>,
+}
+
+#[derive(Deserialize)]
+struct JsonTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+    /// The repeat count, which Aseprite exports as a string
+    #[serde(default)]
+    repeat: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonSlice {
+    name: String,
+    keys: Vec<JsonSliceKey>,
+}
+
+#[derive(Deserialize)]
+struct JsonSliceKey {
+    frame: usize,
+    bounds: JsonRect,
+    center: Option<JsonRect>,
+    pivot: Option<JsonPoint>,
+}
+
+/// Deserialize the frames from either an array or a map, keeping them in the order that they are
+/// in the file
+fn deserialize_frames<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<JsonFrame>, D::Error> {
+    struct FramesVisitor;
+
+    impl<'de> de::Visitor<'de> for FramesVisitor {
+        type Value = Vec<JsonFrame>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("an array or map of frames")
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut frames = Vec::new();
+            while let Some(frame) = seq.next_element()? {
+                frames.push(frame);
+            }
+            Ok(frames)
+        }
+
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut frames = Vec::new();
+            while let Some((_, frame)) = map.next_entry::<de::IgnoredAny, JsonFrame>()? {
+                frames.push(frame);
+            }
+            Ok(frames)
+        }
+    }
+
+    deserializer.deserialize_any(FramesVisitor)
+}
+
+/// Loader for [`Aseprite`] sprites from Aseprite JSON sprite sheet exports, with the
+/// `.aseprite.json` extension
+///
+/// The sheet must be exported with the "Tags" and "Slices" meta options enabled for them to be
+/// loaded, and trimmed frames are not supported.
+#[derive(Default)]
+pub struct AsepriteJsonLoader;
+
+impl AssetLoader for AsepriteJsonLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let sheet: JsonSheet = serde_json::from_slice(bytes)?;
+
+            // The image path is relative to the JSON file
+            let image_path = load_context
+                .path()
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(&sheet.meta.image);
+            let image_path = AssetPath::new(image_path, None);
+            let texture = load_context.get_handle(image_path.clone());
+
+            let frames = sheet
+                .frames
+                .iter()
+                .map(|frame| SheetFrame {
+                    rect: (&frame.frame).into(),
+                    duration: frame.duration as f32 / 1000.0,
+                })
+                .collect::<Vec<_>>();
+
+            let mut clips = AnimationClips::default();
+            for tag in &sheet.meta.frame_tags {
+                clips.clips.insert(
+                    tag.name.clone(),
+                    tag_clip(
+                        &frames,
+                        tag.from,
+                        tag.to,
+                        &tag.direction,
+                        tag.repeat.as_ref().and_then(|x| x.parse().ok()),
+                    ),
+                );
+            }
+
+            let mut slices = HashMap::<String, AsepriteSlice>::default();
+            for slice in &sheet.meta.slices {
+                let keys = &mut slices.entry(slice.name.clone()).or_default().keys;
+                for key in &slice.keys {
+                    keys.push(AsepriteSliceKey {
+                        frame: key.frame,
+                        bounds: (&key.bounds).into(),
+                        center: key.center.as_ref().map(Rect::from),
+                        pivot: key.pivot.as_ref().map(|x| Vec2::new(x.x, x.y)),
+                    });
+                }
+                keys.sort_by_key(|key| key.frame);
+            }
+
+            set_aseprite_asset(
+                load_context,
+                SpriteData {
+                    texture,
+                    size: Vec2::new(sheet.meta.size.w, sheet.meta.size.h),
+                    frames,
+                    clips,
+                    slices,
+                },
+                vec![image_path],
+            );
+
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
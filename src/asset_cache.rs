@@ -0,0 +1,311 @@
+//! Asset handle cache
+
+use std::{
+    any::TypeId,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bevy::{
+    asset::{Asset, AssetPath, AssetPathId, HandleId, LoadState},
+    ecs::{event::ManualEventReader, system::SystemParam},
+    prelude::*,
+};
+use dashmap::DashMap;
+
+use crate::RetroAssetGroups;
+
+/// Whether the [`RetroAssetCache`] holds strong or weak asset handles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssetCachePolicy {
+    /// The cache holds strong handles, which keep cached assets loaded until they are removed from
+    /// the cache.
+    #[default]
+    Strong,
+    /// The cache holds weak handles, so cached assets are unloaded once every other handle to them
+    /// has been dropped, and they are then evicted from the cache.
+    Weak,
+}
+
+/// Statistics about the usage of a [`RetroAssetCache`], useful for debugging
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetCacheStats {
+    /// The number of loads that returned a cached handle
+    pub hits: u64,
+    /// The number of loads that had to load the asset from the asset server
+    pub misses: u64,
+    /// The number of handles currently in the cache
+    pub size: usize,
+}
+
+/// An asset handle cache, used by [`CachedAssetServer`]
+///
+/// Handles are cached by both their asset path and their asset type, so loading the same path as
+/// two different asset types will cache two different handles.
+///
+/// Cached assets are evicted from the cache when they are removed from the asset store. See
+/// [`AssetCachePolicy`] for whether or not the cache keeps its assets loaded.
+#[derive(Resource, Default)]
+pub struct RetroAssetCache {
+    /// Whether the cache holds strong or weak handles. Changing this only affects assets that are
+    /// cached after the change.
+    pub policy: AssetCachePolicy,
+    handles: DashMap<(AssetPathId, TypeId), HandleUntyped>,
+    /// Functions that evict removed assets from the cache, for each cached asset type
+    pruners: DashMap<TypeId, fn(&mut World)>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RetroAssetCache {
+    /// Create an empty cache with the given policy
+    pub fn new(policy: AssetCachePolicy) -> Self {
+        Self {
+            policy,
+            ..default()
+        }
+    }
+
+    /// Load an asset and add it to the cache, or if it has already been loaded, get the cached
+    /// asset handle.
+    pub fn load<'a, T, P>(&self, asset_server: &AssetServer, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset,
+    {
+        // Get the path and ID of the asset we are to load
+        let path = path.into();
+        let id = path.get_id();
+        let key = (id, TypeId::of::<T>());
+        let weak = self.policy == AssetCachePolicy::Weak;
+
+        // If the asset cache has the asset in it
+        let cached = self.handles.get(&key).map(|handle| handle.clone());
+        if let Some(handle) = cached {
+            if !handle.is_weak() {
+                // Return the cached asset
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return handle.typed();
+
+            // Weak handles may point to assets that have been unloaded, but not yet pruned
+            } else if asset_server.get_load_state(id) != LoadState::Unloaded {
+                // Return a new strong handle to the cached asset
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return asset_server.get_handle(id);
+            }
+        }
+
+        // Load the asset
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let handle = asset_server.load(path);
+
+        // Cache its handle
+        self.handles.insert(
+            key,
+            if weak {
+                handle.clone_weak_untyped()
+            } else {
+                handle.clone_untyped()
+            },
+        );
+
+        // And make sure that the cache will be pruned when assets of this type are removed
+        self.pruners
+            .entry(TypeId::of::<T>())
+            .or_insert(prune_asset_cache_for::<T>);
+
+        // And return the handle
+        handle
+    }
+
+    /// Remove a handle from the cache
+    pub fn remove<T: Asset>(&self, handle: &Handle<T>) {
+        let type_id = TypeId::of::<T>();
+        self.handles
+            .retain(|(_, t), v| *t != type_id || v.id() != handle.id());
+    }
+
+    /// Remove every handle from the cache
+    pub fn clear(&self) {
+        self.handles.clear();
+    }
+
+    /// Get the usage statistics for the cache
+    pub fn stats(&self) -> AssetCacheStats {
+        AssetCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            size: self.handles.len(),
+        }
+    }
+}
+
+/// The position of the asset cache pruner in the [`AssetEvent`]s for the asset type `T`
+#[derive(Resource)]
+struct AssetCachePruneReader<T: Asset>(ManualEventReader<AssetEvent<T>>);
+
+/// Evict the handles of any removed assets of type `T` from the asset cache
+fn prune_asset_cache_for<T: Asset>(world: &mut World) {
+    let mut reader = world
+        .remove_resource::<AssetCachePruneReader<T>>()
+        .unwrap_or_else(|| AssetCachePruneReader(default()));
+
+    if let (Some(events), Some(cache)) = (
+        world.get_resource::<Events<AssetEvent<T>>>(),
+        world.get_resource::<RetroAssetCache>(),
+    ) {
+        for event in reader.0.iter(events) {
+            if let AssetEvent::Removed { handle } = event {
+                if let HandleId::AssetPathId(id) = handle.id() {
+                    cache.handles.remove(&(id, TypeId::of::<T>()));
+                }
+            }
+        }
+    }
+
+    world.insert_resource(reader);
+}
+
+/// System that evicts removed assets from the [`RetroAssetCache`]
+pub(crate) fn prune_asset_cache(world: &mut World) {
+    let Some(cache) = world.get_resource::<RetroAssetCache>() else {
+        return;
+    };
+    let pruners = cache
+        .pruners
+        .iter()
+        .map(|pruner| *pruner.value())
+        .collect::<Vec<_>>();
+
+    for prune in pruners {
+        prune(world);
+    }
+}
+
+/// System param that gives access to the [`AssetServer`] along with the [`RetroAssetCache`] and
+/// the [`RetroAssetGroups`]
+///
+/// This dereferences to the [`AssetServer`], and adds the [`AssetServerExt`] functions.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands, asset_server: CachedAssetServer) {
+///     commands.spawn(SpriteBundle {
+///         texture: asset_server.load_cached("redRadish.png"),
+///         ..default()
+///     });
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct CachedAssetServer<'w> {
+    asset_server: Res<'w, AssetServer>,
+    cache: Res<'w, RetroAssetCache>,
+    groups: Res<'w, RetroAssetGroups>,
+}
+
+impl<'w> CachedAssetServer<'w> {
+    /// Get the asset cache
+    pub fn cache(&self) -> &RetroAssetCache {
+        &self.cache
+    }
+
+    /// Get the asset loading groups
+    pub fn groups(&self) -> &RetroAssetGroups {
+        &self.groups
+    }
+}
+
+impl<'w> std::ops::Deref for CachedAssetServer<'w> {
+    type Target = AssetServer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.asset_server
+    }
+}
+
+/// Extension functions for loading assets through the [`RetroAssetCache`]
+pub trait AssetServerExt {
+    /// Load an asset and add it to the [`RetroAssetCache`], or if it has already been loaded, get
+    /// the cached asset handle.
+    ///
+    /// **This is provided by an extension trait to the Bevy asset server.**
+    ///
+    /// Cached assets are evicted from the cache when they are removed from the asset store. See
+    /// [`AssetCachePolicy`] for whether or not the cache keeps its assets loaded.
+    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset;
+
+    /// Remove a handle from the asset cache.
+    ///
+    /// **This is provided by an extension trait to the Bevy asset server.**
+    fn remove_from_cache<T: Asset>(&self, handle: &Handle<T>);
+
+    /// Load an asset through the asset cache and add it to a loading group in the
+    /// [`RetroAssetGroups`], so that its progress is reported in the
+    /// [`LoadingProgress`][crate::LoadingProgress].
+    ///
+    /// **This is provided by an extension trait to the Bevy asset server.**
+    fn load_grouped<'a, T, P>(&self, group: &str, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset;
+}
+
+impl<'w> AssetServerExt for CachedAssetServer<'w> {
+    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset,
+    {
+        self.cache.load(&self.asset_server, path)
+    }
+
+    fn remove_from_cache<T: Asset>(&self, handle: &Handle<T>) {
+        self.cache.remove(handle);
+    }
+
+    fn load_grouped<'a, T, P>(&self, group: &str, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset,
+    {
+        let handle = self.load_cached(path);
+        self.groups.add(group, &handle);
+
+        handle
+    }
+}
+
+/// The asset cache functions that used to be implemented on the Bevy [`AssetServer`] itself
+///
+/// These don't use the app's [`RetroAssetCache`]. The [`AssetServer`] already returns the same
+/// handle for every load of a path, so loading is forwarded to it. Load assets with the
+/// [`CachedAssetServer`] system param instead.
+pub trait LegacyAssetServerExt {
+    /// Load an asset with [`AssetServer::load`]
+    #[deprecated(note = "use `load_cached` on the `CachedAssetServer` system param instead")]
+    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset;
+
+    /// Does nothing, because assets loaded with [`load_cached`][Self::load_cached] aren't cached
+    #[deprecated(note = "use `remove_from_cache` on the `CachedAssetServer` system param instead")]
+    fn remove_from_cache<T: Asset>(handle: Handle<T>);
+}
+
+impl LegacyAssetServerExt for AssetServer {
+    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
+    where
+        P: Into<AssetPath<'a>>,
+        T: Asset,
+    {
+        self.load(path)
+    }
+
+    fn remove_from_cache<T: Asset>(_handle: Handle<T>) {}
+}
@@ -0,0 +1,675 @@
+//! Camera utilities
+
+use bevy::{
+    core_pipeline::{
+        clear_color::ClearColorConfig,
+        tonemapping::{DebandDither, Tonemapping},
+    },
+    prelude::*,
+    render::{
+        camera::{CameraRenderGraph, RenderTarget, ScalingMode, Viewport},
+        primitives::Frustum,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        texture::ImageSampler,
+        view::{RenderLayers, VisibleEntities},
+    },
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+
+/// The render layer used to draw the upscaled output of pixel-perfect cameras to the window
+pub const RETRO_UPSCALE_LAYER: u8 = RenderLayers::TOTAL_LAYERS as u8 - 1;
+
+/// System sets for the Bevy Retrograde camera systems, which run in [`PostUpdate`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RetroCameraSet {
+    /// Moves cameras that have a [`RetroCameraFollow`][crate::RetroCameraFollow]
+    Follow,
+    /// Keeps cameras that have a `RetroCameraLevelBounds` inside of the selected LDtk level
+    Bounds,
+    /// Offsets the [`GlobalTransform`] of cameras that have a
+    /// [`CameraShake`][crate::CameraShake]
+    Shake,
+}
+
+/// 2D camera with easy controls for sizing the screen
+#[derive(Bundle)]
+pub struct RetroCameraBundle {
+    pub camera: Camera,
+    pub camera_render_graph: CameraRenderGraph,
+    pub projection: OrthographicProjection,
+    pub visible_entities: VisibleEntities,
+    pub frustum: Frustum,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub camera_2d: Camera2d,
+    pub tonemapping: Tonemapping,
+    pub deband_dither: DebandDither,
+    pub retro_camera: RetroCamera,
+}
+
+impl RetroCameraBundle {
+    fn new(scale: f32, scaling_mode: ScalingMode) -> Self {
+        // Modify the projection
+        let orthographic_projection = OrthographicProjection {
+            scale,
+            scaling_mode,
+            ..Default::default()
+        };
+
+        // And copy the rest of the components from the default 2D camera
+        let bundle = Camera2dBundle::default();
+        Self {
+            camera: bundle.camera,
+            projection: orthographic_projection,
+            visible_entities: bundle.visible_entities,
+            frustum: bundle.frustum,
+            transform: bundle.transform,
+            global_transform: bundle.global_transform,
+            camera_2d: bundle.camera_2d,
+            tonemapping: bundle.tonemapping,
+            deband_dither: bundle.deband_dither,
+            camera_render_graph: bundle.camera_render_graph,
+            retro_camera: default(),
+        }
+    }
+
+    /// Create a camera with a fixed width in pixels and a height determined by the window aspect
+    pub fn fixed_width(width: f32) -> Self {
+        Self::new(width / 2.0, ScalingMode::FixedHorizontal(1.))
+    }
+
+    /// Create a camera with a fixed width in pixels and a height determined by the window aspect
+    pub fn fixed_height(height: f32) -> Self {
+        Self::new(height / 2.0, ScalingMode::FixedVertical(1.))
+    }
+
+    /// Create a camera that renders the world to an offscreen image at the given resolution, and
+    /// then draws that image to the window, scaled up by the largest whole number that fits.
+    ///
+    /// Unlike [`fixed_width`][Self::fixed_width] and [`fixed_height`][Self::fixed_height], rotated
+    /// sprites and sprites at sub-pixel positions are still drawn on the game's pixel grid.
+    pub fn pixel_perfect(resolution: UVec2) -> Self {
+        let mut bundle = Self::new(1.0, ScalingMode::WindowSize(1.0));
+        bundle.retro_camera.mode = RetroCameraMode::PixelPerfect { resolution };
+        bundle
+    }
+
+    /// Create a camera that renders directly to the window, where each game pixel is drawn as a
+    /// square of window pixels sized by the largest whole number that fits the given resolution
+    /// inside of the window. The rest of the window is letterboxed.
+    pub fn integer_scaled(resolution: UVec2) -> Self {
+        let mut bundle = Self::new(1.0, ScalingMode::WindowSize(1.0));
+        bundle.retro_camera.mode = RetroCameraMode::IntegerScaled {
+            resolution,
+            letterbox: true,
+        };
+        bundle
+    }
+
+    /// Create a camera that is scaled like [`integer_scaled`][Self::integer_scaled], but that
+    /// fills the whole window by showing more of the world instead of letterboxing it.
+    pub fn integer_scaled_expand(min_resolution: UVec2) -> Self {
+        let mut bundle = Self::new(1.0, ScalingMode::WindowSize(1.0));
+        bundle.retro_camera.mode = RetroCameraMode::IntegerScaled {
+            resolution: min_resolution,
+            letterbox: false,
+        };
+        bundle
+    }
+}
+
+/// Bevy Retrograde camera settings, added by the [`RetroCameraBundle`]
+#[derive(Component, Debug, Clone)]
+pub struct RetroCamera {
+    /// How the camera view is drawn to the window
+    pub mode: RetroCameraMode,
+    /// The color drawn in the space around the camera view that is not covered by the game
+    ///
+    /// **Default:** [`Color::BLACK`]
+    pub letterbox_color: Color,
+}
+
+impl Default for RetroCamera {
+    fn default() -> Self {
+        Self {
+            mode: default(),
+            letterbox_color: Color::BLACK,
+        }
+    }
+}
+
+/// How a [`RetroCamera`] is drawn to the window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetroCameraMode {
+    /// The camera renders directly to the window, sized by its [`OrthographicProjection`].
+    #[default]
+    Projection,
+    /// The camera renders to an offscreen image with the given resolution, which is then scaled up
+    /// to the window with nearest-neighbor filtering by a whole number, with the rest of the window
+    /// letterboxed.
+    PixelPerfect { resolution: UVec2 },
+    /// The camera renders directly to the window, with its projection set so that each game pixel
+    /// covers a whole number of window pixels. If `letterbox` is `true`, only `resolution` pixels
+    /// of the world will be visible and the rest of the window will be letterboxed, otherwise the
+    /// visible area will expand to fill the window.
+    IntegerScaled { resolution: UVec2, letterbox: bool },
+}
+
+impl RetroCameraMode {
+    /// Get the game resolution for modes that are scaled by whole numbers
+    pub fn resolution(&self) -> Option<UVec2> {
+        match self {
+            RetroCameraMode::Projection => None,
+            RetroCameraMode::PixelPerfect { resolution }
+            | RetroCameraMode::IntegerScaled { resolution, .. } => Some(*resolution),
+        }
+    }
+
+    /// Whether or not the space around the game is letterboxed in this mode
+    pub fn is_letterboxed(&self) -> bool {
+        match self {
+            RetroCameraMode::Projection => false,
+            RetroCameraMode::PixelPerfect { .. } => true,
+            RetroCameraMode::IntegerScaled { letterbox, .. } => *letterbox,
+        }
+    }
+}
+
+/// The whole-number scale and window viewport of the game, for cameras in the
+/// [`PixelPerfect`][RetroCameraMode::PixelPerfect] or
+/// [`IntegerScaled`][RetroCameraMode::IntegerScaled] modes
+///
+/// This resource is updated whenever the window is resized, and is only present while there is
+/// such a camera. The UI scale is kept in sync with it, so that a pixel in egui will be the same
+/// size as a pixel in our sprites.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetroCameraScale {
+    /// The number of physical window pixels along each side of a game pixel
+    pub scale: u32,
+    /// The physical position of the top-left corner of the game in the window
+    pub viewport_position: UVec2,
+    /// The physical size of the game in the window
+    pub viewport_size: UVec2,
+}
+
+impl RetroCameraScale {
+    /// Calculate the scale and viewport of a game with the given resolution in a window of the
+    /// given physical size
+    pub fn new(window_size: UVec2, resolution: UVec2, letterbox: bool) -> Self {
+        let scale = integer_scale(window_size, resolution);
+
+        if letterbox {
+            let game_size = resolution * scale;
+            Self {
+                scale,
+                viewport_position: (window_size.max(game_size) - game_size) / 2,
+                viewport_size: game_size.min(window_size),
+            }
+        } else {
+            Self {
+                scale,
+                viewport_position: UVec2::ZERO,
+                viewport_size: window_size,
+            }
+        }
+    }
+
+    /// The size of a game pixel in logical window pixels, given the window's scale factor
+    pub fn logical_scale(&self, scale_factor: f64) -> f64 {
+        self.scale as f64 / scale_factor
+    }
+}
+
+/// The offscreen image that a pixel-perfect [`RetroCamera`] renders to
+///
+/// This is added automatically to cameras in [`RetroCameraMode::PixelPerfect`] mode.
+#[derive(Component, Debug, Clone)]
+pub struct RetroRenderTarget {
+    pub image: Handle<Image>,
+}
+
+/// Marker for cameras that have had their output camera and letterbox spawned
+#[derive(Component)]
+pub(crate) struct RetroOutput;
+
+/// Marker for the camera, sprite, and letterbox bars used to draw a [`RetroCamera`] to the window
+#[derive(Component)]
+pub(crate) struct RetroUpscale {
+    /// The camera that this entity draws the output for
+    pub source: Entity,
+}
+
+/// Marker for one of the four sprites used to draw the letterbox of an integer scaled camera
+#[derive(Component)]
+pub(crate) struct RetroLetterboxBar(usize);
+
+/// Get the largest whole number that `resolution` can be multiplied by and still fit inside of
+/// `window_size`, with a minimum of `1`
+pub fn integer_scale(window_size: UVec2, resolution: UVec2) -> u32 {
+    (window_size / resolution.max(UVec2::ONE))
+        .min_element()
+        .max(1)
+}
+
+/// Create the image that a pixel-perfect camera will render to
+fn create_render_target_image(resolution: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        render_target_extent(resolution),
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image.sampler_descriptor = ImageSampler::nearest();
+
+    image
+}
+
+fn render_target_extent(resolution: UVec2) -> Extent3d {
+    let resolution = resolution.max(UVec2::ONE);
+    Extent3d {
+        width: resolution.x,
+        height: resolution.y,
+        depth_or_array_layers: 1,
+    }
+}
+
+/// System that creates, resizes, and removes the offscreen render targets and output cameras for
+/// [`RetroCamera`]s when their mode changes
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_render_targets(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut cameras: Query<
+        (
+            Entity,
+            &mut Camera,
+            &RetroCamera,
+            Option<&RetroRenderTarget>,
+            Option<&RetroOutput>,
+        ),
+        Changed<RetroCamera>,
+    >,
+) {
+    let layer = RenderLayers::layer(RETRO_UPSCALE_LAYER);
+
+    for (entity, mut camera, retro_camera, render_target, output) in &mut cameras {
+        // Spawn the camera that draws the letterbox and the upscaled render target to the window.
+        // The output entities for cameras switched back to the projection mode will be cleaned up
+        // by `update_upscale`.
+        let needs_output = retro_camera.mode != RetroCameraMode::Projection;
+        let has_output = output.is_some();
+        if needs_output && !has_output {
+            commands.spawn((
+                Camera2dBundle {
+                    camera: Camera {
+                        order: camera.order + 1,
+                        ..default()
+                    },
+                    camera_2d: Camera2d {
+                        clear_color: ClearColorConfig::None,
+                    },
+                    ..default()
+                },
+                layer,
+                RetroUpscale { source: entity },
+            ));
+            for i in 0..4 {
+                commands.spawn((
+                    SpriteBundle {
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    layer,
+                    RetroUpscale { source: entity },
+                    RetroLetterboxBar(i),
+                ));
+            }
+            commands.entity(entity).insert(RetroOutput);
+        } else if !needs_output && has_output {
+            commands.entity(entity).remove::<RetroOutput>();
+        }
+
+        // Only integer scaled cameras have their viewport set
+        if !matches!(retro_camera.mode, RetroCameraMode::IntegerScaled { .. }) {
+            camera.viewport = None;
+        }
+
+        match (retro_camera.mode, render_target) {
+            // Resize the render target if the resolution has changed
+            (RetroCameraMode::PixelPerfect { resolution }, Some(render_target)) => {
+                let size = render_target_extent(resolution);
+                if let Some(image) = images.get_mut(&render_target.image) {
+                    if image.texture_descriptor.size != size {
+                        image.resize(size);
+                    }
+                }
+            }
+            // Create the render target and the sprite used to draw it to the window
+            (RetroCameraMode::PixelPerfect { resolution }, None) => {
+                let image = images.add(create_render_target_image(resolution));
+                camera.target = RenderTarget::Image(image.clone());
+
+                commands.spawn((
+                    SpriteBundle {
+                        texture: image.clone(),
+                        ..default()
+                    },
+                    layer,
+                    RetroUpscale { source: entity },
+                ));
+
+                commands.entity(entity).insert(RetroRenderTarget { image });
+            }
+            // Go back to rendering to the window
+            (_, Some(_)) => {
+                camera.target = RenderTarget::default();
+                commands.entity(entity).remove::<RetroRenderTarget>();
+            }
+            (_, None) => (),
+        }
+    }
+}
+
+/// System that calculates the [`RetroCameraScale`] and updates the projection and viewport of
+/// integer scaled cameras to match the window size
+pub(crate) fn update_camera_scale(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&RetroCamera, &mut Camera, &mut OrthographicProjection)>,
+    camera_scale: Option<ResMut<RetroCameraScale>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    let scale_factor = window.scale_factor();
+
+    let mut first_scale = None;
+    for (retro_camera, mut camera, mut projection) in &mut cameras {
+        let Some(resolution) = retro_camera.mode.resolution() else {
+            continue;
+        };
+        let letterbox = retro_camera.mode.is_letterboxed();
+        let scale = RetroCameraScale::new(window_size, resolution, letterbox);
+        first_scale.get_or_insert(scale);
+
+        if let RetroCameraMode::IntegerScaled { .. } = retro_camera.mode {
+            let pixels_per_unit = scale.logical_scale(scale_factor) as f32;
+            if !matches!(
+                projection.scaling_mode,
+                ScalingMode::WindowSize(x) if x == pixels_per_unit
+            ) || projection.scale != 1.0
+            {
+                projection.scaling_mode = ScalingMode::WindowSize(pixels_per_unit);
+                projection.scale = 1.0;
+            }
+
+            let viewport_changed = match &camera.viewport {
+                Some(viewport) => {
+                    !letterbox
+                        || viewport.physical_position != scale.viewport_position
+                        || viewport.physical_size != scale.viewport_size
+                }
+                None => letterbox,
+            };
+            if viewport_changed {
+                camera.viewport = letterbox.then(|| Viewport {
+                    physical_position: scale.viewport_position,
+                    physical_size: scale.viewport_size,
+                    ..default()
+                });
+            }
+        }
+    }
+
+    match (first_scale, camera_scale) {
+        (Some(scale), Some(mut camera_scale)) => {
+            if *camera_scale != scale {
+                *camera_scale = scale;
+            }
+        }
+        (Some(scale), None) => commands.insert_resource(scale),
+        (None, Some(_)) => commands.remove_resource::<RetroCameraScale>(),
+        (None, None) => (),
+    }
+}
+
+/// System that positions the upscaled render targets and letterboxes of [`RetroCamera`]s in the
+/// window, and cleans up the output entities of cameras that no longer need them
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_upscale(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    sources: Query<&RetroCamera, With<RetroOutput>>,
+    render_targets: Query<(), With<RetroRenderTarget>>,
+    mut output_cameras: Query<(Entity, &RetroUpscale, &Transform, &mut Camera2d)>,
+    mut upscale_sprites: Query<
+        (Entity, &RetroUpscale, &mut Transform),
+        (Without<Camera2d>, Without<RetroLetterboxBar>),
+    >,
+    mut letterbox_bars: Query<
+        (
+            Entity,
+            &RetroUpscale,
+            &RetroLetterboxBar,
+            &mut Transform,
+            &mut Sprite,
+            &mut Visibility,
+        ),
+        Without<Camera2d>,
+    >,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    let scale_factor = window.scale_factor() as f32;
+
+    // Update the output cameras, and record their positions so the sprites can follow them
+    let mut camera_positions = HashMap::default();
+    for (entity, upscale, transform, mut camera_2d) in &mut output_cameras {
+        let Ok(retro_camera) = sources.get(upscale.source) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        // Pixel-perfect cameras clear the letterbox, and integer scaled cameras draw letterbox bars
+        // on top of the game, because the game camera will have cleared the whole window.
+        match retro_camera.mode {
+            RetroCameraMode::PixelPerfect { .. } => {
+                let color = retro_camera.letterbox_color;
+                if !matches!(camera_2d.clear_color, ClearColorConfig::Custom(c) if c == color) {
+                    camera_2d.clear_color = ClearColorConfig::Custom(color);
+                }
+            }
+            _ => {
+                if !matches!(camera_2d.clear_color, ClearColorConfig::None) {
+                    camera_2d.clear_color = ClearColorConfig::None;
+                }
+            }
+        }
+
+        camera_positions.insert(upscale.source, transform.translation.truncate());
+    }
+
+    for (entity, upscale, mut transform) in &mut upscale_sprites {
+        let (Ok(retro_camera), Ok(())) = (
+            sources.get(upscale.source),
+            render_targets.get(upscale.source),
+        ) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let RetroCameraMode::PixelPerfect { resolution } = retro_camera.mode else {
+            continue;
+        };
+        let camera_position = camera_positions
+            .get(&upscale.source)
+            .copied()
+            .unwrap_or_default();
+
+        let scale = integer_scale(window_size, resolution);
+
+        // If the letterbox can't be split evenly on both sides, offset the image by half of a
+        // window pixel so that game pixels still line up with window pixels.
+        let remainder = (window_size.as_ivec2() - (resolution * scale).as_ivec2()).abs() % 2;
+        let offset = remainder.as_vec2() * 0.5 / scale_factor;
+
+        let translation = (camera_position + offset).extend(transform.translation.z);
+        let scale = Vec3::new(
+            scale as f32 / scale_factor,
+            scale as f32 / scale_factor,
+            1.0,
+        );
+        if transform.translation != translation || transform.scale != scale {
+            transform.translation = translation;
+            transform.scale = scale;
+        }
+    }
+
+    for (entity, upscale, bar, mut transform, mut sprite, mut visibility) in &mut letterbox_bars {
+        let Ok(retro_camera) = sources.get(upscale.source) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let RetroCameraMode::IntegerScaled {
+            resolution,
+            letterbox: true,
+        } = retro_camera.mode
+        else {
+            if *visibility != Visibility::Hidden {
+                *visibility = Visibility::Hidden;
+            }
+            continue;
+        };
+        let camera_position = camera_positions
+            .get(&upscale.source)
+            .copied()
+            .unwrap_or_default();
+
+        // Get the edges of the game viewport in physical pixels from the top-left of the window
+        let scale = RetroCameraScale::new(window_size, resolution, true);
+        let min = scale.viewport_position.as_vec2();
+        let max = (scale.viewport_position + scale.viewport_size).as_vec2();
+        let window = window_size.as_vec2();
+
+        // Get the corners of this bar: the left and right bars cover the full window height, and
+        // the top and bottom bars fit between them.
+        let (top_left, bottom_right) = match bar.0 {
+            0 => (Vec2::ZERO, Vec2::new(min.x, window.y)),
+            1 => (Vec2::new(max.x, 0.0), window),
+            2 => (Vec2::new(min.x, 0.0), Vec2::new(max.x, min.y)),
+            _ => (Vec2::new(min.x, max.y), Vec2::new(max.x, window.y)),
+        };
+
+        // Convert to logical units centered on the output camera, with y pointing up
+        let size = (bottom_right - top_left) / scale_factor;
+        let center = ((top_left + bottom_right) / 2.0 - window / 2.0) / scale_factor;
+        let translation =
+            (camera_position + Vec2::new(center.x, -center.y)).extend(transform.translation.z);
+
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+        if sprite.custom_size != Some(size) || sprite.color != retro_camera.letterbox_color {
+            sprite.custom_size = Some(size);
+            sprite.color = retro_camera.letterbox_color;
+        }
+        if *visibility != Visibility::Inherited {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+/// System that keeps the [`RetroUiScale`][crate::ui::RetroUiScale] in sync with the
+/// [`RetroCameraScale`], so that the UI is scaled by the same whole number as the game
+///
+/// The UI scale is only removed when there is no camera scale if this system inserted it, so that
+/// a UI scale inserted by the user is kept.
+#[cfg(feature = "ui")]
+pub(crate) fn sync_ui_scale(
+    mut commands: Commands,
+    camera_scale: Option<Res<RetroCameraScale>>,
+    ui_scale: Option<ResMut<crate::ui::RetroUiScale>>,
+    mut inserted: Local<bool>,
+) {
+    match (camera_scale, ui_scale) {
+        (Some(camera_scale), Some(mut ui_scale)) => {
+            if ui_scale.0 != camera_scale.scale {
+                ui_scale.0 = camera_scale.scale;
+            }
+        }
+        (Some(camera_scale), None) => {
+            commands.insert_resource(crate::ui::RetroUiScale(camera_scale.scale));
+            *inserted = true;
+        }
+        (None, Some(_)) => {
+            if *inserted {
+                commands.remove_resource::<crate::ui::RetroUiScale>();
+                *inserted = false;
+            }
+        }
+        (None, None) => *inserted = false,
+    }
+}
+
+#[cfg(all(test, feature = "ui"))]
+mod tests {
+    use super::*;
+    use crate::ui::RetroUiScale;
+
+    fn camera_scale(scale: u32) -> RetroCameraScale {
+        RetroCameraScale::new(UVec2::new(320, 180) * scale, UVec2::new(320, 180), false)
+    }
+
+    #[test]
+    fn ui_scale_follows_the_camera_scale() {
+        let mut app = App::new();
+        app.add_systems(Update, sync_ui_scale);
+
+        app.insert_resource(camera_scale(3)).update();
+        assert_eq!(
+            app.world.get_resource::<RetroUiScale>(),
+            Some(&RetroUiScale(3))
+        );
+
+        app.insert_resource(camera_scale(2)).update();
+        assert_eq!(
+            app.world.get_resource::<RetroUiScale>(),
+            Some(&RetroUiScale(2))
+        );
+
+        app.world.remove_resource::<RetroCameraScale>();
+        app.update();
+        assert_eq!(app.world.get_resource::<RetroUiScale>(), None);
+    }
+
+    #[test]
+    fn user_ui_scale_is_kept_without_a_camera_scale() {
+        let mut app = App::new();
+        app.add_systems(Update, sync_ui_scale)
+            .insert_resource(RetroUiScale(4));
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world.get_resource::<RetroUiScale>(),
+            Some(&RetroUiScale(4))
+        );
+
+        // A UI scale that was inserted for the camera is still removed with it
+        app.world.remove_resource::<RetroUiScale>();
+        app.insert_resource(camera_scale(3)).update();
+        app.world.remove_resource::<RetroCameraScale>();
+        app.update();
+        assert_eq!(app.world.get_resource::<RetroUiScale>(), None);
+
+        app.insert_resource(RetroUiScale(4)).update();
+        assert_eq!(
+            app.world.get_resource::<RetroUiScale>(),
+            Some(&RetroUiScale(4))
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Camera clamping to LDtk level bounds
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+/// Component that keeps a camera's view inside of the LDtk level picked by the [`LevelSelection`]
+///
+/// If the level is smaller than the view on either axis, the level will be centered on that axis
+/// instead.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands
+///         .spawn(RetroCameraBundle::fixed_height(180.0))
+///         .insert(RetroCameraLevelBounds);
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct RetroCameraLevelBounds;
+
+/// Get the world-space rectangle covered by the currently selected LDtk level, if it has been
+/// spawned
+fn selected_level_rect(
+    level_selection: &LevelSelection,
+    worlds: &Query<&Handle<LdtkAsset>>,
+    levels: &Query<(&Handle<LdtkLevel>, &GlobalTransform)>,
+    ldtk_assets: &Assets<LdtkAsset>,
+    level_assets: &Assets<LdtkLevel>,
+) -> Option<Rect> {
+    // Find the iid of the selected level, so that `Index` selections can be resolved
+    let iid = worlds
+        .iter()
+        .filter_map(|handle| ldtk_assets.get(handle))
+        .find_map(|ldtk_asset| ldtk_asset.get_level(level_selection))
+        .map(|level| &level.iid)?;
+
+    levels.iter().find_map(|(handle, transform)| {
+        let level = &level_assets.get(handle)?.level;
+        if &level.iid != iid {
+            return None;
+        }
+
+        // Levels are spawned with their bottom-left corner at their translation
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        let min = translation.truncate();
+        let size = Vec2::new(level.px_wid as f32, level.px_hei as f32) * scale.truncate();
+        Some(Rect::from_corners(min, min + size))
+    })
+}
+
+/// System that keeps cameras with a [`RetroCameraLevelBounds`] component inside of the selected
+/// level
+pub(crate) fn clamp_camera_to_level(
+    level_selection: Option<Res<LevelSelection>>,
+    worlds: Query<&Handle<LdtkAsset>>,
+    levels: Query<(&Handle<LdtkLevel>, &GlobalTransform)>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    level_assets: Res<Assets<LdtkLevel>>,
+    mut cameras: Query<(&OrthographicProjection, &mut Transform), With<RetroCameraLevelBounds>>,
+) {
+    let Some(level_selection) = level_selection else {
+        return;
+    };
+    let Some(bounds) = selected_level_rect(
+        &level_selection,
+        &worlds,
+        &levels,
+        &ldtk_assets,
+        &level_assets,
+    ) else {
+        return;
+    };
+
+    for (projection, mut transform) in &mut cameras {
+        let half_view = projection.area.half_size() * transform.scale.truncate();
+        let position = transform.translation.truncate();
+
+        // Clamp each axis, or center the level on it if the level is smaller than the view
+        let clamp_axis = |position: f32, half_view: f32, min: f32, max: f32| {
+            if max - min <= half_view * 2.0 {
+                (min + max) / 2.0
+            } else {
+                position.clamp(min + half_view, max - half_view)
+            }
+        };
+        let clamped = Vec2::new(
+            clamp_axis(position.x, half_view.x, bounds.min.x, bounds.max.x),
+            clamp_axis(position.y, half_view.y, bounds.min.y, bounds.max.y),
+        );
+
+        if clamped != position {
+            transform.translation.x = clamped.x;
+            transform.translation.y = clamped.y;
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Camera follow controller
+
+use bevy::prelude::*;
+
+/// Component that makes a camera follow a target entity
+///
+/// The target's [`Transform`] is followed, so it should not be the child of another moving entity.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let player = commands.spawn(SpriteBundle::default()).id();
+///
+///     commands
+///         .spawn(RetroCameraBundle::fixed_height(180.0))
+///         .insert(RetroCameraFollow {
+///             dead_zone: Vec2::new(32.0, 16.0),
+///             look_ahead: Vec2::new(0.25, 0.0),
+///             ..RetroCameraFollow::new(player)
+///         });
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct RetroCameraFollow {
+    /// The entity to follow
+    pub target: Entity,
+    /// The size of a rectangle around the center of the camera that the target can move around in
+    /// without moving the camera
+    ///
+    /// **Default:** [`Vec2::ZERO`]
+    pub dead_zone: Vec2,
+    /// The offset from the center of the camera to the center of the dead zone
+    ///
+    /// **Default:** [`Vec2::ZERO`]
+    pub offset: Vec2,
+    /// The time in seconds that it takes the camera to cover most of the distance to where it
+    /// needs to be. A value of `0` makes the camera move instantly.
+    ///
+    /// **Default:** `0.1`
+    pub smoothing: f32,
+    /// The number of seconds of the target's velocity to look ahead of the target on each axis
+    ///
+    /// **Default:** [`Vec2::ZERO`]
+    pub look_ahead: Vec2,
+    /// The maximum distance that the camera will look ahead of the target on each axis
+    ///
+    /// **Default:** `(64.0, 64.0)`
+    pub max_look_ahead: Vec2,
+    /// Whether or not to round the camera translation to a whole pixel
+    ///
+    /// **Default:** `true`
+    pub pixel_snap: bool,
+}
+
+impl RetroCameraFollow {
+    /// Follow the given entity with the default settings
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            dead_zone: Vec2::ZERO,
+            offset: Vec2::ZERO,
+            smoothing: 0.1,
+            look_ahead: Vec2::ZERO,
+            max_look_ahead: Vec2::splat(64.0),
+            pixel_snap: true,
+        }
+    }
+}
+
+/// The un-snapped state of a [`RetroCameraFollow`]
+#[derive(Component, Default)]
+pub(crate) struct RetroCameraFollowState {
+    /// The exact camera position, before pixel snapping
+    position: Option<Vec2>,
+    /// The target position on the previous frame, used to calculate its velocity
+    last_target_position: Option<Vec2>,
+    /// The current look-ahead offset from the target
+    look_ahead: Vec2,
+}
+
+/// Get the fraction of the remaining distance to move this frame, for the given smoothing time
+fn smoothing_factor(smoothing: f32, delta_seconds: f32) -> f32 {
+    if smoothing <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-delta_seconds / smoothing).exp()
+    }
+}
+
+/// System that moves cameras with a [`RetroCameraFollow`] component towards their targets
+pub(crate) fn update_camera_follow(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut cameras: Query<(
+        Entity,
+        &RetroCameraFollow,
+        Option<&mut RetroCameraFollowState>,
+        &mut Transform,
+    )>,
+    targets: Query<&Transform, Without<RetroCameraFollow>>,
+) {
+    let delta_seconds = time.delta_seconds();
+
+    for (entity, follow, state, mut transform) in &mut cameras {
+        let Some(mut state) = state else {
+            commands
+                .entity(entity)
+                .insert(RetroCameraFollowState::default());
+            continue;
+        };
+        let Ok(target) = targets.get(follow.target) else {
+            continue;
+        };
+        let target_position = target.translation.truncate();
+        let mut position = state
+            .position
+            .unwrap_or_else(|| transform.translation.truncate());
+
+        // Look ahead in the direction the target is moving
+        let velocity = match state.last_target_position {
+            Some(last) if delta_seconds > 0.0 => (target_position - last) / delta_seconds,
+            _ => Vec2::ZERO,
+        };
+        let look_ahead = (velocity * follow.look_ahead)
+            .clamp(-follow.max_look_ahead.abs(), follow.max_look_ahead.abs());
+        state.look_ahead = state.look_ahead.lerp(
+            look_ahead,
+            smoothing_factor(follow.smoothing, delta_seconds),
+        );
+        let focus = target_position + state.look_ahead;
+
+        // Only move the camera enough to keep the focus point inside of the dead zone
+        let half_dead_zone = follow.dead_zone.abs() / 2.0;
+        let zone_center = position + follow.offset;
+        let goal = position + (focus - zone_center)
+            - (focus - zone_center).clamp(-half_dead_zone, half_dead_zone);
+
+        position = position.lerp(goal, smoothing_factor(follow.smoothing, delta_seconds));
+
+        state.position = Some(position);
+        state.last_target_position = Some(target_position);
+
+        let translation = if follow.pixel_snap {
+            position.round()
+        } else {
+            position
+        };
+        if transform.translation.truncate() != translation {
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Trauma-based camera shake
+
+use bevy::prelude::*;
+
+/// Component that shakes a camera based on an amount of "trauma" that decays over time
+///
+/// The shake is applied to the camera's [`GlobalTransform`] after transforms have been propagated,
+/// so the camera's [`Transform`] is never changed and can still be moved by other systems, such as
+/// the [`RetroCameraFollow`][crate::RetroCameraFollow] controller.
+///
+/// Trauma can be added by modifying the component directly, or by sending a [`ShakeCamera`] event.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands
+///         .spawn(RetroCameraBundle::fixed_height(180.0))
+///         .insert(CameraShake::default());
+/// }
+///
+/// fn on_explosion(mut shake_events: EventWriter<ShakeCamera>) {
+///     shake_events.send(ShakeCamera { trauma: 0.5 });
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct CameraShake {
+    /// The current trauma, from `0` to `1`. The strength of the shake is the square of the trauma.
+    ///
+    /// **Default:** `0.0`
+    pub trauma: f32,
+    /// The amount of trauma removed every second
+    ///
+    /// **Default:** `1.0`
+    pub decay: f32,
+    /// The offset of the camera in pixels at full trauma
+    ///
+    /// **Default:** `(8.0, 8.0)`
+    pub max_offset: Vec2,
+    /// The rotation of the camera in radians at full trauma
+    ///
+    /// **Default:** `0.05`
+    pub max_rotation: f32,
+    /// How fast the shake changes direction, in noise samples per second
+    ///
+    /// **Default:** `15.0`
+    pub frequency: f32,
+    /// Whether or not to round the shake offset to a whole pixel
+    ///
+    /// **Default:** `true`
+    pub pixel_snap: bool,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.0,
+            max_offset: Vec2::splat(8.0),
+            max_rotation: 0.05,
+            frequency: 15.0,
+            pixel_snap: true,
+        }
+    }
+}
+
+impl CameraShake {
+    /// Add trauma to the shake, keeping the total trauma at or below `1`
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+}
+
+/// Event that adds trauma to every camera with a [`CameraShake`] component
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShakeCamera {
+    /// The amount of trauma to add, from `0` to `1`
+    pub trauma: f32,
+}
+
+/// Hash an integer lattice point into a value from `-1` to `1`
+fn hash_noise(seed: u32, i: i32) -> f32 {
+    let mut x = (i as u32).wrapping_mul(0x27d4_eb2d) ^ seed.wrapping_mul(0x1656_67b1);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2c1b_3c6d);
+    x ^= x >> 12;
+    x = x.wrapping_mul(0x297a_2d39);
+    x ^= x >> 15;
+
+    x as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+/// Smooth 1D value noise from `-1` to `1`
+fn noise(seed: u32, t: f32) -> f32 {
+    let i = t.floor();
+    let f = t - i;
+    let a = hash_noise(seed, i as i32);
+    let b = hash_noise(seed, i as i32 + 1);
+
+    a + (b - a) * (f * f * (3.0 - 2.0 * f))
+}
+
+/// System that adds the trauma from [`ShakeCamera`] events to cameras
+pub(crate) fn apply_shake_events(
+    mut events: EventReader<ShakeCamera>,
+    mut cameras: Query<&mut CameraShake>,
+) {
+    for event in events.iter() {
+        for mut shake in &mut cameras {
+            shake.add_trauma(event.trauma);
+        }
+    }
+}
+
+/// System that offsets the [`GlobalTransform`] of shaking cameras and decays their trauma
+pub(crate) fn shake_cameras(
+    time: Res<Time>,
+    mut cameras: Query<(&mut CameraShake, &mut Transform, &mut GlobalTransform)>,
+) {
+    let t = time.elapsed_seconds_wrapped();
+
+    for (mut shake, mut base_transform, mut global_transform) in &mut cameras {
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+
+        let strength = shake.trauma * shake.trauma;
+        let sample = t * shake.frequency;
+        let mut offset =
+            Vec2::new(noise(0, sample), noise(1, sample)) * shake.max_offset * strength;
+        if shake.pixel_snap {
+            offset = offset.round();
+        }
+        let rotation = noise(2, sample) * shake.max_rotation * strength;
+
+        let mut transform = global_transform.compute_transform();
+        transform.translation += offset.extend(0.0);
+        transform.rotation = Quat::from_rotation_z(rotation) * transform.rotation;
+        *global_transform = transform.into();
+
+        // Mark the base transform as changed so that the global transform is re-calculated without
+        // the shake next frame.
+        base_transform.set_changed();
+
+        let decay = shake.decay * time.delta_seconds();
+        shake.trauma = (shake.trauma - decay).max(0.0);
+    }
+}
@@ -0,0 +1,188 @@
+//! CRT screen effects
+
+use bevy::{asset::load_internal_asset, prelude::*, reflect::TypeUuid, utils::HashMap};
+
+use crate::{
+    screen_effect::{
+        add_screen_effect, init_screen_effect_pipeline, sync_screen_effect, ScreenAreas,
+        ScreenEffect,
+    },
+    RetroCamera,
+};
+
+const CRT_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 8363451427979593741);
+
+/// Plugin that renders the [`CrtEffect`]s of cameras
+///
+/// This is added by the [`RetroPlugins`][crate::RetroPlugins].
+pub struct RetroCrtPlugin;
+
+impl Plugin for RetroCrtPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, CRT_SHADER_HANDLE, "crt.wgsl", Shader::from_wgsl);
+
+        add_screen_effect::<CrtSettings>(app);
+        app.add_systems(
+            PostUpdate,
+            update_crt_settings.after(crate::camera::update_upscale),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        init_screen_effect_pipeline::<CrtSettings>(app);
+    }
+}
+
+/// Component that draws a camera's view like it is on an old CRT screen
+///
+/// The effect is applied to the window after the game has been scaled up to it, so that the
+/// scanlines line up with the game's pixels and the phosphor mask lines up with the window's
+/// pixels. For cameras in the [`PixelPerfect`][crate::RetroCameraMode::PixelPerfect] and
+/// [`IntegerScaled`][crate::RetroCameraMode::IntegerScaled] modes only the game area is affected,
+/// not the letterbox.
+///
+/// Each effect can be turned off by setting its strength to `0.0`, and the whole effect can be
+/// turned off with [`enabled`][Self::enabled], for example to give players an accessibility option.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RetroCameraBundle::pixel_perfect(UVec2::new(160, 90)),
+///         CrtEffect {
+///             curvature: 0.0,
+///             ..default()
+///         },
+///     ));
+/// }
+///
+/// fn toggle_crt(keyboard: Res<Input<KeyCode>>, mut effects: Query<&mut CrtEffect>) {
+///     if keyboard.just_pressed(KeyCode::C) {
+///         for mut effect in &mut effects {
+///             effect.enabled = !effect.enabled;
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct CrtEffect {
+    /// Whether or not the effect is drawn
+    ///
+    /// **Default:** `true`
+    pub enabled: bool,
+    /// How much the edges of each row of game pixels are darkened, from `0.0` to `1.0`
+    ///
+    /// **Default:** `0.3`
+    pub scanlines: f32,
+    /// How much the screen bulges outwards. Parts of the game that are bent off of the screen are
+    /// drawn black.
+    ///
+    /// **Default:** `0.03`
+    pub curvature: f32,
+    /// How far apart the red and blue channels are drawn at the edges of the screen, in game pixels
+    ///
+    /// **Default:** `0.3`
+    pub chromatic_aberration: f32,
+    /// How much the corners of the screen are darkened
+    ///
+    /// **Default:** `0.2`
+    pub vignette: f32,
+    /// How much each column of window pixels is tinted red, green, or blue, from `0.0` to `1.0`
+    ///
+    /// **Default:** `0.15`
+    pub phosphor_mask: f32,
+}
+
+impl Default for CrtEffect {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scanlines: 0.3,
+            curvature: 0.03,
+            chromatic_aberration: 0.3,
+            vignette: 0.2,
+            phosphor_mask: 0.15,
+        }
+    }
+}
+
+use settings::CrtSettings;
+
+// The `ShaderType` derive generates assertion functions that are never called, so the settings are
+// kept in their own module to allow the dead code warnings without hiding any others.
+#[allow(dead_code)]
+mod settings {
+    use bevy::{
+        prelude::*,
+        render::{extract_component::ExtractComponent, render_resource::ShaderType},
+    };
+
+    /// The [`CrtEffect`][super::CrtEffect] settings that are sent to the shader, added to the
+    /// camera that draws the effect to the window
+    #[derive(Component, ExtractComponent, ShaderType, Debug, Clone, Copy, PartialEq)]
+    pub struct CrtSettings {
+        /// The position and size of the game area in physical pixels
+        pub viewport: Vec4,
+        /// The number of physical pixels along each side of a game pixel
+        pub pixel_scale: f32,
+        pub scanlines: f32,
+        pub curvature: f32,
+        pub chromatic_aberration: f32,
+        pub vignette: f32,
+        pub phosphor_mask: f32,
+    }
+}
+
+impl ScreenEffect for CrtSettings {
+    const NAME: &'static str = "retro_crt";
+
+    fn shader() -> Handle<Shader> {
+        CRT_SHADER_HANDLE.typed()
+    }
+}
+
+/// System that adds the [`CrtSettings`] to the cameras that draw [`CrtEffect`]s to the window
+///
+/// Cameras that are drawn to the window by an output camera have the effect applied by the output
+/// camera, so that it happens after the game is scaled up.
+fn update_crt_settings(
+    mut commands: Commands,
+    screen_areas: ScreenAreas,
+    cameras: Query<(
+        Entity,
+        &Camera,
+        &OrthographicProjection,
+        Option<&RetroCamera>,
+        &CrtEffect,
+    )>,
+    current_settings: Query<(Entity, &CrtSettings)>,
+) {
+    let mut new_settings = HashMap::default();
+    for (entity, camera, projection, retro_camera, effect) in &cameras {
+        if !effect.enabled {
+            continue;
+        }
+        let Some(area) = screen_areas.get(entity, camera, projection, retro_camera) else {
+            continue;
+        };
+
+        new_settings.insert(
+            area.camera,
+            CrtSettings {
+                viewport: area.viewport,
+                pixel_scale: area.pixel_scale,
+                scanlines: effect.scanlines,
+                curvature: effect.curvature,
+                chromatic_aberration: effect.chromatic_aberration,
+                vignette: effect.vignette,
+                phosphor_mask: effect.phosphor_mask,
+            },
+        );
+    }
+
+    sync_screen_effect(&mut commands, new_settings, &current_settings);
+}
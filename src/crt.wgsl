@@ -0,0 +1,67 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader  FullscreenVertexOutput
+
+struct CrtSettings {
+    // The game area of the screen, as the position and size in physical pixels
+    viewport: vec4<f32>,
+    // The number of physical pixels along each side of a game pixel
+    pixel_scale: f32,
+    scanlines: f32,
+    curvature: f32,
+    chromatic_aberration: f32,
+    vignette: f32,
+    phosphor_mask: f32,
+};
+
+@group(0) @binding(0)
+var screen_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var screen_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> settings: CrtSettings;
+
+const PI: f32 = 3.14159265359;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let screen_size = vec2<f32>(textureDimensions(screen_texture));
+
+    // Leave everything outside of the game area, such as the letterbox, alone
+    var position = (in.position.xy - settings.viewport.xy) / settings.viewport.zw;
+    if any(position < vec2<f32>(0.0)) || any(position > vec2<f32>(1.0)) {
+        return textureSampleLevel(screen_texture, screen_sampler, in.uv, 0.0);
+    }
+
+    // Bend the game area outwards like the glass of a CRT
+    var centered = position * 2.0 - 1.0;
+    centered += centered * centered.yx * centered.yx * settings.curvature;
+    if any(abs(centered) > vec2<f32>(1.0)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+    position = centered * 0.5 + 0.5;
+    let uv = (settings.viewport.xy + position * settings.viewport.zw) / screen_size;
+
+    // Split the color channels further apart towards the edges of the screen
+    let offset = centered * settings.chromatic_aberration * settings.pixel_scale / screen_size;
+    var color = vec3<f32>(
+        textureSampleLevel(screen_texture, screen_sampler, uv + offset, 0.0).r,
+        textureSampleLevel(screen_texture, screen_sampler, uv, 0.0).g,
+        textureSampleLevel(screen_texture, screen_sampler, uv - offset, 0.0).b,
+    );
+
+    // Darken the edges of each row of game pixels
+    let row = position.y * settings.viewport.w / max(settings.pixel_scale, 1.0);
+    let line = 0.5 - 0.5 * cos(2.0 * PI * fract(row));
+    color *= mix(1.0 - settings.scanlines, 1.0, line);
+
+    // Tint each column of screen pixels red, green, or blue like the phosphors of an aperture grille
+    let column = u32(in.position.x) % 3u;
+    var mask = vec3<f32>(1.0 - settings.phosphor_mask);
+    mask[column] = 1.0;
+    color *= mask;
+
+    // Darken the corners of the screen
+    let edges = position * (1.0 - position);
+    color *= pow(clamp(edges.x * edges.y * 16.0, 0.0, 1.0), settings.vignette);
+
+    return vec4<f32>(color, 1.0);
+}
@@ -55,9 +55,22 @@
 //! - [LDtk](https://ldtk.io) map loading and rendering using [`bevy_ecs_ldtk`].
 //! - An integration with the [`egui`] UI library with extra 9-patch style widgets.
 //! - Text rendering of bitmap fonts in the BDF format
-//! - Physics and collision detection powered by [Rapier] with automatic generation of convex
-//!   collision shapes from sprite images.
+//! - Physics and collision detection powered by [Rapier] with automatic generation of convex and
+//!   concave collision shapes from sprite images.
 //! - Sound playing with [`bevy_kira_audio`].
+//! - Sprite sheet animation with clips defined in RON files or loaded from [Aseprite] files.
+//! - Palette swapping for sprites, with palettes loaded from images and switched at runtime.
+//! - CRT screen effects with scanlines, curvature, chromatic aberration, vignette, and a phosphor
+//!   mask.
+//! - Fade, dithered wipe, iris, and pixelate screen transitions.
+//! - Sub-pixel positions that are snapped to whole pixels when they are drawn.
+//! - Parallax background layers that can repeat forever.
+//! - Save data stored in named slots, in files on desktop and in `localStorage` on the web.
+//! - Named input actions and axes bound to the keyboard, mouse, and gamepads, which can be rebound
+//!   at runtime and saved.
+//! - A headless mode for running game logic in tests without a window or GPU, and the
+//!   `bevy_retrograde_test` crate for stepping frames, simulating input, and checking collisions
+//!   in tests.
 //!
 //! [examples]:
 //! https://github.com/katharostech/bevy_retrograde/tree/master/examples#bevy-retro-examples
@@ -68,6 +81,8 @@
 //!
 //! [`bevy_kira_audio`]: https://github.com/NiklasEi/bevy_kira_audio
 //!
+//! [Aseprite]: https://www.aseprite.org/
+//!
 //! # Supported Bevy Version
 //!
 //!
@@ -86,45 +101,168 @@
 //! ```
 #![allow(ambiguous_glob_reexports)]
 use bevy::{
-    asset::{Asset, AssetPath, AssetPathId},
-    core_pipeline::tonemapping::{DebandDither, Tonemapping},
     prelude::*,
-    render::{
-        camera::{Camera, CameraRenderGraph, ScalingMode},
-        primitives::Frustum,
-        view::VisibleEntities,
-    },
+    render::{camera::CameraUpdateSystem, view::VisibilitySystems, ExtractSchedule, RenderApp},
+    sprite::SpriteSystem,
+    transform::TransformSystem,
 };
-use dashmap::DashMap;
+
+pub mod animation;
+pub use animation::*;
+
+#[cfg(feature = "aseprite")]
+pub mod aseprite;
+#[cfg(feature = "aseprite")]
+pub use aseprite::*;
+
+pub mod asset_cache;
+pub use asset_cache::*;
+
+pub mod palette_swap;
+pub use palette_swap::*;
+
+pub mod parallax;
+pub use parallax::*;
+
+pub mod pixel_position;
+pub use pixel_position::*;
+
+pub mod loading;
+pub use loading::*;
+
+pub mod camera;
+pub use camera::*;
+
+pub mod camera_follow;
+pub use camera_follow::*;
+
+pub mod camera_shake;
+pub use camera_shake::*;
+
+pub mod crt;
+pub use crt::*;
+
+mod screen_effect;
+
+pub mod transition;
+pub use transition::*;
+
+#[cfg(feature = "ldtk")]
+pub mod camera_bounds;
+#[cfg(feature = "ldtk")]
+pub use camera_bounds::*;
 
 /// Bevy Retrograde default plugins
+///
+/// Use [`RetroPlugins::headless`] to run game logic without a window or GPU, such as in tests on
+/// CI machines.
 pub struct RetroPlugins {
-    /// Used to calculate the physics scale, if the physics feature is enabled.
+    /// The number of pixels in one meter of the physics simulation, if the physics feature is
+    /// enabled. See [`RetroPhysicsPlugin`][crate::physics::RetroPhysicsPlugin].
+    ///
+    /// **Default:** `8.0`
     pub pixels_per_meter: f32,
+    /// Whether the [`RetroAssetCache`] keeps cached assets loaded
+    pub asset_cache_policy: AssetCachePolicy,
+    /// Whether to run without a window, a GPU, or audio
+    ///
+    /// Headless apps start from Bevy's [`MinimalPlugins`] instead of the [`DefaultPlugins`], and
+    /// add only the Bevy plugins that assets, physics, LDtk maps, and UI fonts need. Components for
+    /// rendering, such as sprites and cameras, can still be spawned, but nothing is drawn. Audio is
+    /// left out, so systems that use the audio resources won't run in headless apps.
+    ///
+    /// **Default:** `false`
+    pub headless: bool,
 }
 
 impl Default for RetroPlugins {
     fn default() -> Self {
         Self {
+            #[cfg(feature = "physics")]
+            pixels_per_meter: physics::DEFAULT_PIXELS_PER_METER,
+            #[cfg(not(feature = "physics"))]
             pixels_per_meter: 8.0,
+            asset_cache_policy: default(),
+            headless: false,
+        }
+    }
+}
+
+impl RetroPlugins {
+    /// Create the plugins for an app that runs without a window, a GPU, or audio
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_retrograde::prelude::*;
+    /// let mut app = App::new();
+    /// app.add_plugins(RetroPlugins::headless());
+    ///
+    /// // Step the game a frame at a time
+    /// app.update();
+    /// ```
+    pub fn headless() -> Self {
+        Self {
+            headless: true,
+            ..default()
         }
     }
 }
 
+/// The Bevy plugins used by headless [`RetroPlugins`]
+fn headless_bevy_plugins() -> bevy::app::PluginGroupBuilder {
+    use bevy::{
+        core_pipeline::CorePipelinePlugin,
+        input::InputPlugin,
+        render::{settings::WgpuSettings, RenderPlugin},
+        sprite::SpritePlugin,
+        window::ExitCondition,
+    };
+
+    MinimalPlugins
+        .build()
+        .add(TransformPlugin)
+        .add(HierarchyPlugin)
+        .add(InputPlugin)
+        .add(WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            close_when_requested: false,
+        })
+        .add(AssetPlugin::default())
+        // Without any backends the renderer doesn't look for a GPU, but the render assets and
+        // components are still registered
+        .add(RenderPlugin {
+            wgpu_settings: WgpuSettings {
+                backends: None,
+                ..default()
+            },
+        })
+        .add(ImagePlugin::default())
+        .add(CorePipelinePlugin)
+        .add(SpritePlugin)
+}
+
 impl bevy::app::PluginGroup for RetroPlugins {
     fn build(self) -> bevy::app::PluginGroupBuilder {
         // Add the plugins we need from Bevy
-        let group = bevy::DefaultPlugins.build();
+        let group = if self.headless {
+            headless_bevy_plugins()
+        } else {
+            bevy::DefaultPlugins.build()
+        };
 
         #[cfg(feature = "audio")]
-        let group = group.add(audio::AudioPlugin);
+        let group = if self.headless {
+            group
+        } else {
+            group.add(audio::AudioPlugin)
+        };
 
         #[cfg(feature = "ldtk")]
         let group = group.add(ldtk::LdtkPlugin);
 
-        #[cfg(feature = "text")]
-        let group = group.add(text::RetroTextPlugin);
-
         #[cfg(feature = "physics")]
         let group = group.add(physics::RetroPhysicsPlugin {
             pixels_per_meter: self.pixels_per_meter,
@@ -133,7 +271,26 @@ impl bevy::app::PluginGroup for RetroPlugins {
         #[cfg(feature = "ui")]
         let group = group.add(ui::RetroUiPlugin);
 
-        group.add(RetroCorePlugin)
+        #[cfg(feature = "save")]
+        let group = group.add(save::RetroSavePlugin::default());
+
+        #[cfg(feature = "input")]
+        let group = group.add(input::RetroInputPlugin);
+
+        let group = group.add(RetroAnimationPlugin);
+
+        let group = group.add(RetroPaletteSwapPlugin);
+
+        let group = group.add(RetroParallaxPlugin);
+
+        // Transitions are added before the CRT effect so that they are drawn under it
+        let group = group.add(RetroTransitionPlugin);
+
+        let group = group.add(RetroCrtPlugin);
+
+        group.add(RetroCorePlugin {
+            asset_cache_policy: self.asset_cache_policy,
+        })
     }
 }
 
@@ -154,6 +311,12 @@ pub mod prelude {
 
     #[cfg(feature = "physics")]
     pub use bevy_retrograde_physics::prelude::*;
+
+    #[cfg(feature = "save")]
+    pub use bevy_retrograde_save::prelude::*;
+
+    #[cfg(feature = "input")]
+    pub use bevy_retrograde_input::prelude::*;
 }
 
 pub use bevy_retrograde_macros::impl_deref;
@@ -168,18 +331,121 @@ pub use bevy_retrograde_physics as physics;
 #[cfg(feature = "ldtk")]
 pub use bevy_ecs_ldtk as ldtk;
 
+#[cfg(feature = "save")]
+#[doc(inline)]
+pub use bevy_retrograde_save as save;
+
+#[cfg(feature = "input")]
+#[doc(inline)]
+pub use bevy_retrograde_input as input;
+
 #[cfg(feature = "ui")]
 #[doc(inline)]
 pub use bevy_retrograde_ui as ui;
 
 /// The Core Bevy plugin
-struct RetroCorePlugin;
+struct RetroCorePlugin {
+    asset_cache_policy: AssetCachePolicy,
+}
 
 impl Plugin for RetroCorePlugin {
-    #[cfg_attr(not(target_arch = "wasm32"), allow(unused))]
     fn build(&self, app: &mut App) {
         #[cfg(target_arch = "wasm32")]
         app.add_system(update_canvas_size);
+
+        app.insert_resource(RetroAssetCache::new(self.asset_cache_policy))
+            .add_systems(Last, asset_cache::prune_asset_cache);
+
+        let asset_groups = RetroAssetGroups::default();
+        #[cfg(feature = "ui")]
+        asset_groups.register_dependencies::<ui::fonts::RetroFont>();
+        #[cfg(feature = "ldtk")]
+        {
+            asset_groups.register_dependencies::<ldtk::LdtkAsset>();
+            asset_groups.register_dependencies::<ldtk::LdtkLevel>();
+        }
+        app.insert_resource(asset_groups)
+            .init_resource::<LoadingProgress>()
+            .add_systems(PostUpdate, loading::update_loading_progress);
+
+        app.add_systems(
+            PostUpdate,
+            (
+                camera::update_render_targets,
+                camera::update_camera_scale,
+                camera::update_upscale.before(TransformSystem::TransformPropagate),
+            )
+                .chain()
+                .before(CameraUpdateSystem),
+        );
+
+        app.add_systems(
+            PostUpdate,
+            camera_follow::update_camera_follow
+                .in_set(RetroCameraSet::Follow)
+                .before(TransformSystem::TransformPropagate),
+        );
+
+        app.add_event::<ShakeCamera>().add_systems(
+            PostUpdate,
+            (
+                camera_shake::apply_shake_events,
+                camera_shake::shake_cameras
+                    .in_set(RetroCameraSet::Shake)
+                    .after(TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::UpdateOrthographicFrusta),
+            )
+                .chain(),
+        );
+
+        app.add_systems(
+            PostUpdate,
+            pixel_position::snap_pixel_positions
+                .in_set(PixelSnapSet::Transform)
+                .before(RetroCameraSet::Follow)
+                .before(TransformSystem::TransformPropagate),
+        );
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(
+                ExtractSchedule,
+                pixel_position::snap_extracted_sprites
+                    .in_set(PixelSnapSet::Render)
+                    .after(SpriteSystem::ExtractSprites),
+            );
+        }
+
+        #[cfg(feature = "ldtk")]
+        app.add_systems(
+            PostUpdate,
+            camera_bounds::clamp_camera_to_level
+                .in_set(RetroCameraSet::Bounds)
+                .after(RetroCameraSet::Follow)
+                .before(TransformSystem::TransformPropagate),
+        );
+
+        // Follow physics bodies after their positions have been written for this frame
+        #[cfg(feature = "physics")]
+        app.configure_set(
+            PostUpdate,
+            RetroCameraSet::Follow.after(physics::bevy_rapier2d::plugin::PhysicsSet::Writeback),
+        );
+
+        // Rigid bodies are only snapped when they are drawn, so that they simulate smoothly
+        #[cfg(feature = "physics")]
+        app.configure_set(
+            PostUpdate,
+            PixelSnapSet::Transform.after(physics::bevy_rapier2d::plugin::PhysicsSet::Writeback),
+        )
+        .add_systems(
+            PostUpdate,
+            pixel_position::render_only_rigid_bodies.before(PixelSnapSet::Transform),
+        );
+
+        #[cfg(feature = "ui")]
+        app.add_systems(
+            PostUpdate,
+            camera::sync_ui_scale.after(camera::update_camera_scale),
+        );
     }
 }
 
@@ -196,118 +462,3 @@ pub fn update_canvas_size(mut windows: ResMut<Windows>) {
     // Set the canvas to the browser size
     window.set_resolution(window_width as f32, window_height as f32);
 }
-
-/// 2D camera with easy controls for sizing the screen
-#[derive(Bundle)]
-pub struct RetroCameraBundle {
-    pub camera: Camera,
-    pub camera_render_graph: CameraRenderGraph,
-    pub projection: OrthographicProjection,
-    pub visible_entities: VisibleEntities,
-    pub frustum: Frustum,
-    pub transform: Transform,
-    pub global_transform: GlobalTransform,
-    pub camera_2d: Camera2d,
-    pub tonemapping: Tonemapping,
-    pub deband_dither: DebandDither,
-}
-
-impl RetroCameraBundle {
-    fn new(scale: f32, scaling_mode: ScalingMode) -> Self {
-        // Modify the projection
-        let orthographic_projection = OrthographicProjection {
-            scale,
-            scaling_mode,
-            ..Default::default()
-        };
-
-        // And copy the rest of the components from the default 2D camera
-        let bundle = Camera2dBundle::default();
-        Self {
-            camera: bundle.camera,
-            projection: orthographic_projection,
-            visible_entities: bundle.visible_entities,
-            frustum: bundle.frustum,
-            transform: bundle.transform,
-            global_transform: bundle.global_transform,
-            camera_2d: bundle.camera_2d,
-            tonemapping: bundle.tonemapping,
-            deband_dither: bundle.deband_dither,
-            camera_render_graph: bundle.camera_render_graph,
-        }
-    }
-
-    /// Create a camera with a fixed width in pixels and a height determined by the window aspect
-    pub fn fixed_width(width: f32) -> Self {
-        Self::new(width / 2.0, ScalingMode::FixedHorizontal(1.))
-    }
-
-    /// Create a camera with a fixed width in pixels and a height determined by the window aspect
-    pub fn fixed_height(height: f32) -> Self {
-        Self::new(height / 2.0, ScalingMode::FixedVertical(1.))
-    }
-}
-
-lazy_static::lazy_static! {
-    /// An asset handle cache used by [`AssetServerExt`]
-    static ref ASSET_CACHE: DashMap<AssetPathId, HandleUntyped> = DashMap::new();
-}
-
-/// Extension functions for the Bevy [`AssetServer`]
-pub trait AssetServerExt {
-    /// Load an asset and add it to an internal cache, or if it has already been loaded, get the
-    /// cached asset handle.
-    ///
-    /// **This is provided by an extension trait to the Bevy asset server.**
-    ///
-    /// # Note
-    ///
-    /// If the asset that has previously been cached is being loaded and it has been manually
-    /// removed from the asset store, the handle returned by this function will point to an
-    /// un-loaded asset and the asset must be re-loaded with the normal `load` function.
-    // TODO: Create a system that will prune the asset cache by listening for asset removed events
-    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
-    where
-        P: Into<AssetPath<'a>>,
-        T: Asset;
-
-    /// Remove a handle from the asset cache. It is recommended to do this for any cached assets
-    /// that are manually removed to prevent the cached handle being returned for a non-existent
-    /// asset.
-    ///
-    /// **This is provided by an extension trait to the Bevy asset server.**
-    fn remove_from_cache<T: Asset>(handle: Handle<T>);
-}
-
-impl AssetServerExt for AssetServer {
-    fn load_cached<'a, T, P>(&self, path: P) -> Handle<T>
-    where
-        P: Into<AssetPath<'a>>,
-        T: Asset,
-    {
-        // Get the path and ID of the asset we are to load
-        let path = path.into();
-        let id = path.get_id();
-
-        // If the asset cache has the asset in it
-        if let Some(handle) = ASSET_CACHE.get(&id) {
-            // Return the cached asset
-            handle.clone().typed()
-
-        // If the asset cache doesn't have the asset
-        } else {
-            // Load the asset
-            let handle = self.load(path);
-
-            // Cache its handle
-            ASSET_CACHE.insert(id, handle.clone_untyped());
-
-            // And return the handle
-            handle
-        }
-    }
-
-    fn remove_from_cache<T: Asset>(handle: Handle<T>) {
-        ASSET_CACHE.retain(|_, v| v != &handle.clone_untyped());
-    }
-}
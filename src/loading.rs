@@ -0,0 +1,422 @@
+//! Asset preloading groups and loading progress
+
+use std::any::TypeId;
+
+use bevy::{
+    asset::{Asset, HandleId, LoadState},
+    prelude::*,
+    utils::HashMap,
+};
+use dashmap::DashMap;
+
+/// An asset that has been added to a loading group
+struct GroupAsset {
+    handle: HandleUntyped,
+    type_id: TypeId,
+    /// Whether or not the asset's [`LoadingDependencies`] have been added to the group yet
+    dependencies_added: bool,
+}
+
+/// Function that gets the dependencies of a loaded asset, or `None` if the asset isn't loaded
+type DependencyResolver = fn(&World, &HandleUntyped) -> Option<AssetDependencies>;
+
+/// Named groups of assets that are loaded together, such as all of the assets needed for a level
+///
+/// Assets are usually added to groups with [`AssetServerExt::load_grouped`], and the loading
+/// progress of all groups is reported by the [`LoadingProgress`] resource. Groups hold strong
+/// handles to their assets, so the assets stay loaded until the group is removed.
+///
+/// [`AssetServerExt::load_grouped`]: crate::AssetServerExt::load_grouped
+#[derive(Resource, Default)]
+pub struct RetroAssetGroups {
+    groups: DashMap<String, Vec<GroupAsset>>,
+    resolvers: DashMap<TypeId, DependencyResolver>,
+}
+
+impl RetroAssetGroups {
+    /// Add an asset handle to a loading group, creating the group if it doesn't exist
+    ///
+    /// Adding an asset that is already in the group does nothing.
+    pub fn add<T: Asset>(&self, group: &str, handle: &Handle<T>) {
+        let type_id = TypeId::of::<T>();
+        let mut assets = self.groups.entry(group.to_string()).or_default();
+        if assets
+            .iter()
+            .any(|asset| asset.handle.id() == handle.id() && asset.type_id == type_id)
+        {
+            return;
+        }
+        assets.push(GroupAsset {
+            handle: handle.clone_untyped(),
+            type_id,
+            dependencies_added: false,
+        });
+    }
+
+    /// Remove a loading group, dropping its handles to its assets
+    pub fn remove_group(&self, group: &str) {
+        self.groups.remove(group);
+    }
+
+    /// Get whether or not a loading group exists
+    pub fn contains_group(&self, group: &str) -> bool {
+        self.groups.contains_key(group)
+    }
+
+    /// Make the [`LoadingDependencies`] of the asset type `T` count towards the progress of any
+    /// loading group that `T` is added to
+    pub fn register_dependencies<T: LoadingDependencies>(&self) {
+        self.resolvers
+            .insert(TypeId::of::<T>(), resolve_dependencies::<T>);
+    }
+}
+
+/// A list of asset handles that an asset depends on
+#[derive(Default)]
+pub struct AssetDependencies(Vec<(HandleUntyped, TypeId)>);
+
+impl AssetDependencies {
+    /// Add a dependency to the list
+    pub fn add<T: Asset>(&mut self, handle: &Handle<T>) {
+        self.0.push((handle.clone_untyped(), TypeId::of::<T>()));
+    }
+}
+
+/// Trait for asset types that depend on other assets that must be loaded before they can be used,
+/// such as the texture of a font
+///
+/// Dependencies only count towards loading progress for types registered with
+/// [`RetroAssetGroups::register_dependencies`]. Dependencies are registered for the built-in asset
+/// types by default.
+pub trait LoadingDependencies: Asset {
+    /// Add the dependencies of the asset to the list
+    fn loading_dependencies(&self, dependencies: &mut AssetDependencies);
+}
+
+fn resolve_dependencies<T: LoadingDependencies>(
+    world: &World,
+    handle: &HandleUntyped,
+) -> Option<AssetDependencies> {
+    let asset = world
+        .get_resource::<Assets<T>>()?
+        .get(&handle.typed_weak())?;
+    let mut dependencies = AssetDependencies::default();
+    asset.loading_dependencies(&mut dependencies);
+
+    Some(dependencies)
+}
+
+#[cfg(feature = "ui")]
+impl LoadingDependencies for crate::ui::fonts::RetroFont {
+    fn loading_dependencies(&self, dependencies: &mut AssetDependencies) {
+        dependencies.add(&self.data.texture);
+    }
+}
+
+#[cfg(feature = "ldtk")]
+impl LoadingDependencies for crate::ldtk::LdtkAsset {
+    fn loading_dependencies(&self, dependencies: &mut AssetDependencies) {
+        for tileset in self.tileset_map.values() {
+            dependencies.add(tileset);
+        }
+        for level in self.level_map.values() {
+            dependencies.add(level);
+        }
+        if let Some(int_grid_image) = &self.int_grid_image_handle {
+            dependencies.add(int_grid_image);
+        }
+    }
+}
+
+#[cfg(feature = "ldtk")]
+impl LoadingDependencies for crate::ldtk::LdtkLevel {
+    fn loading_dependencies(&self, dependencies: &mut AssetDependencies) {
+        if let Some(background) = &self.background_image {
+            dependencies.add(background);
+        }
+    }
+}
+
+/// Resource reporting the loading progress of the assets in the [`RetroAssetGroups`]
+///
+/// The counts include the [`LoadingDependencies`] of assets, such as the texture of a font, so
+/// the total may grow as assets finish loading.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadingProgress {
+    /// The number of assets that have loaded
+    pub loaded: usize,
+    /// The number of assets that have failed to load
+    pub failed: usize,
+    /// The total number of assets
+    pub total: usize,
+    groups: HashMap<String, LoadingProgress>,
+}
+
+impl LoadingProgress {
+    /// Get the progress of a single loading group, which will be empty if the group doesn't
+    /// exist
+    pub fn group(&self, group: &str) -> LoadingProgress {
+        self.groups.get(group).cloned().unwrap_or_default()
+    }
+
+    /// Get the fraction of assets that have finished loading or failed, from `0` to `1`
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+
+    /// Whether or not every asset has either loaded or failed
+    ///
+    /// This is `false` while there are no assets, because the assets may not have been added yet.
+    pub fn is_finished(&self) -> bool {
+        self.total > 0 && self.loaded + self.failed == self.total
+    }
+
+    /// Whether or not every asset has loaded successfully
+    ///
+    /// This is `false` while there are no assets, because the assets may not have been added yet.
+    pub fn is_ready(&self) -> bool {
+        self.total > 0 && self.loaded == self.total
+    }
+
+    fn add(&mut self, other: &LoadingProgress) {
+        self.loaded += other.loaded;
+        self.failed += other.failed;
+        self.total += other.total;
+    }
+}
+
+/// System that updates the [`LoadingProgress`] and adds loaded assets' dependencies to their
+/// groups
+pub(crate) fn update_loading_progress(world: &mut World) {
+    let mut progress = LoadingProgress::default();
+
+    {
+        let groups = world.resource::<RetroAssetGroups>();
+        let asset_server = world.resource::<AssetServer>();
+
+        for mut group in groups.groups.iter_mut() {
+            let mut group_progress = LoadingProgress::default();
+            let assets = group.value_mut();
+
+            // Dependencies are added to the end of the list as their parents load
+            let mut i = 0;
+            while i < assets.len() {
+                let asset = &mut assets[i];
+                i += 1;
+                group_progress.total += 1;
+
+                // Assets that weren't loaded by the asset server are already in memory
+                let load_state = match asset.handle.id() {
+                    HandleId::AssetPathId(id) => asset_server.get_load_state(id),
+                    HandleId::Id(..) => LoadState::Loaded,
+                };
+
+                match load_state {
+                    LoadState::Loaded => group_progress.loaded += 1,
+                    LoadState::Failed => {
+                        group_progress.failed += 1;
+                        continue;
+                    }
+                    _ => continue,
+                }
+
+                if asset.dependencies_added {
+                    continue;
+                }
+                let Some(resolve) = groups.resolvers.get(&asset.type_id).map(|x| *x) else {
+                    asset.dependencies_added = true;
+                    continue;
+                };
+                if let Some(dependencies) = resolve(world, &asset.handle) {
+                    asset.dependencies_added = true;
+                    assets.extend(
+                        dependencies
+                            .0
+                            .into_iter()
+                            .map(|(handle, type_id)| GroupAsset {
+                                handle,
+                                type_id,
+                                dependencies_added: false,
+                            }),
+                    );
+                }
+            }
+
+            progress.add(&group_progress);
+            progress.groups.insert(group.key().clone(), group_progress);
+        }
+    }
+
+    if world.get_resource::<LoadingProgress>() != Some(&progress) {
+        world.insert_resource(progress);
+    }
+}
+
+/// Plugin that transitions out of a loading state once the assets in its loading groups have loaded
+///
+/// The plugin waits until each of its [`groups`][Self::groups] has at least one asset and all of
+/// them have finished loading, so assets may be added to the groups a few frames after entering the
+/// loading state. If no groups are given, it waits for every group in the [`RetroAssetGroups`].
+///
+/// The [`RetroPlugins`][crate::RetroPlugins] must also be added, and the state must be added to
+/// the app with [`App::add_state`].
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// #[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// enum GameState {
+///     #[default]
+///     Loading,
+///     Playing,
+/// }
+///
+/// fn build(app: &mut App) {
+///     app.add_state::<GameState>()
+///         .add_plugins(
+///             RetroLoadingPlugin::new(GameState::Loading, GameState::Playing).with_group("level1"),
+///         )
+///         .add_systems(OnEnter(GameState::Loading), load_assets);
+/// }
+///
+/// fn load_assets(asset_server: CachedAssetServer) {
+///     let _: Handle<Image> = asset_server.load_grouped("level1", "redRadish.png");
+/// }
+/// ```
+pub struct RetroLoadingPlugin<S: States> {
+    /// The state to wait for assets to load in
+    pub loading_state: S,
+    /// The state to transition to once every asset has loaded
+    pub next_state: S,
+    /// The state to transition to if any asset fails to load. If this is `None`, the app will stay
+    /// in the loading state when an asset fails to load.
+    ///
+    /// **Default:** `None`
+    pub failure_state: Option<S>,
+    /// The loading groups to wait for. If this is empty, every group is waited for.
+    ///
+    /// **Default:** `[]`
+    pub groups: Vec<String>,
+}
+
+impl<S: States> RetroLoadingPlugin<S> {
+    /// Transition from the loading state to the next state once every asset has loaded
+    pub fn new(loading_state: S, next_state: S) -> Self {
+        Self {
+            loading_state,
+            next_state,
+            failure_state: None,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Wait for the assets in the given loading group
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.groups.push(group.to_string());
+        self
+    }
+}
+
+impl<S: States> Plugin for RetroLoadingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        let next_state = self.next_state.clone();
+        let failure_state = self.failure_state.clone();
+        let groups = self.groups.clone();
+
+        app.add_systems(
+            PostUpdate,
+            (move |progress: Res<LoadingProgress>, mut state: ResMut<NextState<S>>| {
+                let (finished, failed) = if groups.is_empty() {
+                    (progress.is_finished(), progress.failed)
+                } else {
+                    groups.iter().fold((true, 0), |(finished, failed), group| {
+                        let progress = progress.group(group);
+                        (finished && progress.is_finished(), failed + progress.failed)
+                    })
+                };
+
+                if !finished {
+                    return;
+                }
+                if failed == 0 {
+                    state.set(next_state.clone());
+                } else if let Some(failure_state) = &failure_state {
+                    state.set(failure_state.clone());
+                }
+            })
+            .after(update_loading_progress)
+            .run_if(in_state(self.loading_state.clone())),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetServerExt, CachedAssetServer, RetroPlugins};
+
+    #[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    enum GameState {
+        #[default]
+        Loading,
+        Playing,
+    }
+
+    #[test]
+    fn waits_for_assets_added_after_entering_the_loading_state() {
+        let mut app = App::new();
+        app.add_plugins(RetroPlugins::headless())
+            .add_state::<GameState>()
+            .add_plugins(
+                RetroLoadingPlugin::new(GameState::Loading, GameState::Playing).with_group("level"),
+            );
+
+        // Assets in other groups aren't waited for
+        app.world
+            .resource::<RetroAssetGroups>()
+            .add("other", &Handle::<Image>::default());
+
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(
+            *app.world.resource::<State<GameState>>().get(),
+            GameState::Loading
+        );
+
+        app.add_systems(Update, |asset_server: CachedAssetServer| {
+            let _: Handle<Image> = asset_server.load_grouped("level", "redRadish.png");
+        });
+        for _ in 0..1000 {
+            app.update();
+            if *app.world.resource::<State<GameState>>().get() == GameState::Playing {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("The loading state was never left");
+    }
+
+    #[test]
+    fn assets_are_only_added_to_a_group_once() {
+        let mut app = App::new();
+        app.add_plugins(RetroPlugins::headless()).add_systems(
+            Update,
+            |asset_server: CachedAssetServer| {
+                let _: Handle<Image> = asset_server.load_grouped("g", "redRadish.png");
+                let _: Handle<Image> = asset_server.load_grouped("g", "redRadish.png");
+            },
+        );
+
+        for _ in 0..3 {
+            app.update();
+            assert_eq!(app.world.resource::<LoadingProgress>().group("g").total, 1);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Palette swapping for sprites
+
+use bevy::{
+    asset::{load_internal_asset, HandleId},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    render::{
+        render_resource::{AsBindGroup, Extent3d, ShaderRef, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    sprite::{Material2d, Material2dPlugin, Mesh2dHandle},
+    utils::HashMap,
+};
+
+const PALETTE_SWAP_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 5741282495977846249);
+
+/// Plugin that renders [`PaletteSwap`] sprites
+///
+/// This is added by the [`RetroPlugins`][crate::RetroPlugins].
+pub struct RetroPaletteSwapPlugin;
+
+impl Plugin for RetroPaletteSwapPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PALETTE_SWAP_SHADER_HANDLE,
+            "palette_swap.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(Material2dPlugin::<PaletteSwapMaterial>::default())
+            .init_resource::<PaletteSwapMaterials>()
+            .add_systems(PostUpdate, update_palette_swaps);
+    }
+}
+
+/// Component that renders a sprite with the colors of its source palette replaced by the colors
+/// at the same positions in its target palette
+///
+/// Palettes are images that are one pixel tall, with one pixel for each color, so they can be
+/// loaded with the asset server like any other image, or created at runtime with
+/// [`palette_image`]. Colors in the texture that aren't in the source palette are left unchanged.
+///
+/// Change the [`target_palette`][Self::target_palette] to switch palettes at runtime.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.spawn(PaletteSwapBundle {
+///         palette_swap: PaletteSwap {
+///             texture: asset_server.load("redRadish.png"),
+///             source_palette: asset_server.load("palettes/radish_red.png"),
+///             target_palette: asset_server.load("palettes/radish_blue.png"),
+///             ..default()
+///         },
+///         ..default()
+///     });
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Default)]
+pub struct PaletteSwap {
+    /// The sprite image
+    pub texture: Handle<Image>,
+    /// The palette of the colors in the sprite image
+    pub source_palette: Handle<Image>,
+    /// The palette to replace the colors of the source palette with
+    pub target_palette: Handle<Image>,
+    /// The size to render the sprite at, instead of the size of the image
+    ///
+    /// **Default:** `None`
+    pub custom_size: Option<Vec2>,
+}
+
+/// Bundle for spawning a [`PaletteSwap`] sprite
+#[derive(Bundle, Clone, Default)]
+pub struct PaletteSwapBundle {
+    pub palette_swap: PaletteSwap,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+}
+
+/// The material used to render [`PaletteSwap`] sprites
+#[derive(AsBindGroup, TypeUuid, TypePath, Debug, Clone)]
+#[uuid = "c6934053-659e-4c2a-8940-792547fe6056"]
+pub struct PaletteSwapMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub texture: Handle<Image>,
+    #[texture(2)]
+    pub source_palette: Handle<Image>,
+    #[texture(3)]
+    pub target_palette: Handle<Image>,
+}
+
+impl Material2d for PaletteSwapMaterial {
+    fn fragment_shader() -> ShaderRef {
+        PALETTE_SWAP_SHADER_HANDLE.typed().into()
+    }
+}
+
+/// Create a palette image from a list of colors
+pub fn palette_image(colors: &[Color]) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: colors.len().max(1) as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        colors
+            .iter()
+            .flat_map(|color| color.as_rgba_u8())
+            .chain(std::iter::repeat(0))
+            .take(colors.len().max(1) * 4)
+            .collect(),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler_descriptor = ImageSampler::nearest();
+
+    image
+}
+
+/// Weak handles to the materials used by [`PaletteSwap`]s, so that sprites with the same images
+/// share a material
+#[derive(Resource, Default)]
+struct PaletteSwapMaterials(HashMap<[HandleId; 3], Handle<PaletteSwapMaterial>>);
+
+/// The size of the mesh created for a [`PaletteSwap`]
+#[derive(Component)]
+struct PaletteSwapMesh(Vec2);
+
+/// System that creates the meshes and materials for [`PaletteSwap`] sprites
+#[allow(clippy::type_complexity)]
+fn update_palette_swaps(
+    mut commands: Commands,
+    mut cache: ResMut<PaletteSwapMaterials>,
+    mut materials: ResMut<Assets<PaletteSwapMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    sprites: Query<(
+        Entity,
+        &PaletteSwap,
+        Option<&PaletteSwapMesh>,
+        Option<&Handle<PaletteSwapMaterial>>,
+    )>,
+) {
+    // Forget materials that have been unloaded because no sprites use them anymore
+    cache
+        .0
+        .retain(|_, material| materials.get(&*material).is_some());
+
+    for (entity, palette_swap, mesh, material) in &sprites {
+        let mut entity = commands.entity(entity);
+
+        // Wait for the image to load to find out what size the sprite is
+        let size = palette_swap
+            .custom_size
+            .or_else(|| images.get(&palette_swap.texture).map(|image| image.size()));
+        if let Some(size) = size {
+            if mesh.map(|x| x.0) != Some(size) {
+                entity.insert((
+                    PaletteSwapMesh(size),
+                    Mesh2dHandle(meshes.add(shape::Quad::new(size).into())),
+                ));
+            }
+        }
+
+        let key = [
+            palette_swap.texture.id(),
+            palette_swap.source_palette.id(),
+            palette_swap.target_palette.id(),
+        ];
+        if let (Some(material), Some(cached)) = (material, cache.0.get(&key)) {
+            if material.id() == cached.id() {
+                continue;
+            }
+        }
+        let new_material = match cache.0.get(&key) {
+            Some(material) => materials.get_handle(material),
+            None => {
+                let material = materials.add(PaletteSwapMaterial {
+                    texture: palette_swap.texture.clone(),
+                    source_palette: palette_swap.source_palette.clone(),
+                    target_palette: palette_swap.target_palette.clone(),
+                });
+                cache.0.insert(key, material.clone_weak());
+                material
+            }
+        };
+        entity.insert(new_material);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The same comparison as `colors_match` in `palette_swap.wgsl`, which is given the colors in
+    /// linear space
+    fn colors_match(a: Color, b: Color) -> bool {
+        let [a, b] = [a, b].map(|color| {
+            let [r, g, b, a] = color.as_linear_rgba_f32();
+            Color::rgba_linear(r, g, b, a).as_rgba_f32()
+        });
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.5 / 255.0)
+    }
+
+    #[test]
+    fn adjacent_dark_palette_colors_are_distinct() {
+        let dark = Color::rgb_u8(2, 3, 4);
+        let darker = Color::rgb_u8(1, 3, 4);
+
+        // In linear space the colors are closer than the old tolerance
+        let [a, b] = [dark, darker].map(|color| color.as_linear_rgba_f32());
+        assert!(a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.002));
+
+        assert!(colors_match(dark, dark));
+        assert!(!colors_match(dark, darker));
+    }
+
+    #[test]
+    fn palette_images_keep_their_colors() {
+        let colors = [Color::rgb_u8(1, 2, 3), Color::rgb_u8(2, 2, 3)];
+        let image = palette_image(&colors);
+        assert_eq!(
+            image.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        assert_eq!(image.data, [1, 2, 3, 255, 2, 2, 3, 255]);
+    }
+}
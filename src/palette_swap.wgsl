@@ -0,0 +1,52 @@
+#import bevy_sprite::mesh2d_vertex_output  MeshVertexOutput
+#import bevy_sprite::mesh2d_view_bindings  view
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+@group(1) @binding(0)
+var texture: texture_2d<f32>;
+@group(1) @binding(1)
+var texture_sampler: sampler;
+@group(1) @binding(2)
+var source_palette: texture_2d<f32>;
+@group(1) @binding(3)
+var target_palette: texture_2d<f32>;
+
+// Convert a color from linear space back to the sRGB space that the images are stored in
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+// Colors are compared in sRGB space, where every 8-bit step is the same size. In linear space the
+// steps between dark colors are tiny, so neighboring dark colors would be matched together.
+fn colors_match(a: vec4<f32>, b: vec4<f32>) -> bool {
+    let difference = abs(vec4<f32>(linear_to_srgb(a.rgb), a.a) - vec4<f32>(linear_to_srgb(b.rgb), b.a));
+    return all(difference < vec4<f32>(0.5 / 255.0));
+}
+
+@fragment
+fn fragment(
+    mesh: MeshVertexOutput,
+) -> @location(0) vec4<f32> {
+    var output_color: vec4<f32> = textureSample(texture, texture_sampler, mesh.uv);
+
+    // Replace the color with the color at the same index in the target palette
+    let source_count = textureDimensions(source_palette).x;
+    let target_count = textureDimensions(target_palette).x;
+    for (var i = 0u; i < source_count; i++) {
+        let source_color = textureLoad(source_palette, vec2<u32>(i, 0u), 0);
+        if colors_match(source_color, output_color) {
+            output_color = textureLoad(target_palette, vec2<u32>(min(i, target_count - 1u), 0u), 0);
+            break;
+        }
+    }
+
+#ifdef TONEMAP_IN_SHADER
+    output_color = bevy_core_pipeline::tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+    return output_color;
+}
@@ -0,0 +1,200 @@
+//! Parallax background layers
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{AddressMode, SamplerDescriptor},
+        texture::ImageSampler,
+    },
+    transform::TransformSystem,
+};
+
+use crate::{RetroCamera, RetroCameraSet};
+
+/// Plugin that moves [`ParallaxLayer`]s with the camera
+///
+/// This is added by the [`RetroPlugins`][crate::RetroPlugins].
+pub struct RetroParallaxPlugin;
+
+impl Plugin for RetroParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            update_parallax_layers
+                .after(RetroCameraSet::Follow)
+                .after(RetroCameraSet::Bounds)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Component that moves a sprite relative to a [`RetroCamera`] to make it look nearer or further
+/// away than the rest of the world
+///
+/// The layer's [`Transform`] translation is set from its [`origin`][Self::origin] and the
+/// camera's position every frame, so move the origin instead of the transform.
+///
+/// Layers that [`repeat`][Self::repeat] on an axis are stretched to cover the camera's view on
+/// that axis and draw their image over and over again. The image's sampler is changed to repeat
+/// when it is loaded, so the image shouldn't be used for other sprites that need it to clamp.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.spawn(RetroCameraBundle::fixed_height(180.0));
+///
+///     // Distant mountains that scroll at a quarter of the camera's speed and repeat horizontally
+///     commands.spawn((
+///         SpriteBundle {
+///             texture: asset_server.load("mountains.png"),
+///             transform: Transform::from_xyz(0.0, 0.0, -10.0),
+///             ..default()
+///         },
+///         ParallaxLayer {
+///             repeat: BVec2::new(true, false),
+///             ..ParallaxLayer::new(Vec2::splat(0.25))
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct ParallaxLayer {
+    /// How much the layer moves with the world on each axis when the camera moves. `1.0` moves
+    /// like the rest of the world, `0.0` stays still on the screen, values in between look further
+    /// away, and values above `1.0` look closer.
+    pub factor: Vec2,
+    /// The position of the layer when the camera is at the world origin
+    ///
+    /// **Default:** [`Vec2::ZERO`]
+    pub origin: Vec2,
+    /// Whether to repeat the layer forever on each axis
+    ///
+    /// **Default:** `(false, false)`
+    pub repeat: BVec2,
+    /// Whether or not to round the layer position to a whole pixel
+    ///
+    /// **Default:** `true`
+    pub pixel_snap: bool,
+    /// The camera that the layer moves relative to. If this is `None` the first [`RetroCamera`]
+    /// is used.
+    ///
+    /// **Default:** `None`
+    pub camera: Option<Entity>,
+}
+
+impl ParallaxLayer {
+    /// Create a layer with the given parallax factor and the default settings
+    pub fn new(factor: Vec2) -> Self {
+        Self {
+            factor,
+            origin: Vec2::ZERO,
+            repeat: BVec2::FALSE,
+            pixel_snap: true,
+            camera: None,
+        }
+    }
+}
+
+/// Make sure an image repeats instead of clamping when it is sampled outside of its edges
+fn make_image_repeat(images: &mut Assets<Image>, handle: &Handle<Image>) {
+    let repeats = |descriptor: &SamplerDescriptor| {
+        descriptor.address_mode_u == AddressMode::Repeat
+            && descriptor.address_mode_v == AddressMode::Repeat
+    };
+    if let Some(ImageSampler::Descriptor(descriptor)) =
+        images.get(handle).map(|image| &image.sampler_descriptor)
+    {
+        if repeats(descriptor) {
+            return;
+        }
+    }
+
+    let Some(image) = images.get_mut(handle) else {
+        return;
+    };
+    let mut descriptor = match &image.sampler_descriptor {
+        ImageSampler::Descriptor(descriptor) => descriptor.clone(),
+        ImageSampler::Default => ImageSampler::nearest_descriptor(),
+    };
+    descriptor.address_mode_u = AddressMode::Repeat;
+    descriptor.address_mode_v = AddressMode::Repeat;
+    image.sampler_descriptor = ImageSampler::Descriptor(descriptor);
+}
+
+/// System that positions [`ParallaxLayer`]s relative to their cameras
+#[allow(clippy::type_complexity)]
+fn update_parallax_layers(
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<RetroCamera>>,
+    mut layers: Query<
+        (&ParallaxLayer, &mut Transform, &mut Sprite, &Handle<Image>),
+        Without<RetroCamera>,
+    >,
+) {
+    for (layer, mut transform, mut sprite, image_handle) in &mut layers {
+        let camera = match layer.camera {
+            Some(entity) => cameras.get(entity).ok(),
+            None => cameras.iter().next(),
+        };
+        let Some((camera_transform, projection)) = camera else {
+            continue;
+        };
+        let camera_position = camera_transform.translation.truncate();
+
+        // The position that the layer would have if it didn't repeat
+        let mut position = layer.origin + camera_position * (Vec2::ONE - layer.factor);
+        if layer.pixel_snap {
+            position = position.round();
+        }
+
+        if !layer.repeat.any() {
+            let translation = position.extend(transform.translation.z);
+            if transform.translation != translation {
+                transform.translation = translation;
+            }
+            continue;
+        }
+
+        make_image_repeat(&mut images, image_handle);
+        let Some(image_size) = images.get(image_handle).map(|image| image.size()) else {
+            continue;
+        };
+
+        // Repeating axes are centered on the camera and cover its view, with enough extra room
+        // that the image can be scrolled by up to its whole size
+        let view_size = projection.area.size();
+        let mut center = camera_position;
+        if layer.pixel_snap {
+            center = center.round();
+        }
+        let mut translation = position;
+        let mut size = image_size;
+        let mut rect_min = Vec2::ZERO;
+        if layer.repeat.x {
+            translation.x = center.x;
+            size.x = ((view_size.x + image_size.x) / 2.0).ceil() * 2.0;
+            rect_min.x = (center.x - size.x / 2.0 - position.x + image_size.x / 2.0)
+                .rem_euclid(image_size.x);
+        }
+        if layer.repeat.y {
+            translation.y = center.y;
+            size.y = ((view_size.y + image_size.y) / 2.0).ceil() * 2.0;
+            // Image rows go down while the world goes up
+            rect_min.y = (position.y + image_size.y / 2.0 - center.y - size.y / 2.0)
+                .rem_euclid(image_size.y);
+        }
+
+        let translation = translation.extend(transform.translation.z);
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+        let rect = Some(Rect::from_corners(rect_min, rect_min + size));
+        if sprite.rect != rect || sprite.custom_size != Some(size) {
+            sprite.rect = rect;
+            sprite.custom_size = Some(size);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Sub-pixel positions that are drawn on whole pixels
+
+use bevy::{prelude::*, render::Extract, sprite::ExtractedSprites};
+
+/// System sets for the pixel snapping systems
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PixelSnapSet {
+    /// Writes the [`Transform`]s of entities that are snapped in
+    /// [`PixelSnapMode::Transform`] mode, before transforms are propagated in [`PostUpdate`]
+    Transform,
+    /// Rounds the positions that the sprites of entities that are snapped in
+    /// [`PixelSnapMode::Render`] mode are drawn at, after they are extracted to the render app in
+    /// the [`ExtractSchedule`][bevy::render::ExtractSchedule]
+    Render,
+}
+
+/// Component that stores the precise position of an entity, which is rounded to a whole pixel
+/// before it is drawn
+///
+/// Moving sprites by fractions of a pixel every frame makes them jitter, because they are drawn on
+/// a different pixel depending on how the position happens to round. Move the [`PixelPosition`]
+/// instead of the [`Transform`] and the entity will keep its exact position while being drawn on
+/// the pixel grid. Like the [`Transform`], the position is relative to the entity's parent.
+///
+/// Entities controlled by [`bevy_rapier2d`][crate::physics::bevy_rapier2d] rigid bodies are always
+/// snapped in [`PixelSnapMode::Render`] mode, so that the physics simulation still sees the smooth
+/// position.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((SpriteBundle::default(), PixelPosition::new(Vec2::ZERO)));
+/// }
+///
+/// fn move_right(time: Res<Time>, mut positions: Query<&mut PixelPosition>) {
+///     for mut position in &mut positions {
+///         position.position.x += 12.5 * time.delta_seconds();
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct PixelPosition {
+    /// The precise position of the entity
+    pub position: Vec2,
+    /// How the position is snapped to whole pixels
+    ///
+    /// **Default:** [`PixelSnapMode::Transform`]
+    pub mode: PixelSnapMode,
+}
+
+impl PixelPosition {
+    /// Create a pixel position that is written to the [`Transform`]
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            mode: default(),
+        }
+    }
+
+    /// Create a pixel position that is only snapped when it is drawn, for entities whose
+    /// [`Transform`] is controlled by something else, such as a physics engine
+    pub fn render_only() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            mode: PixelSnapMode::Render,
+        }
+    }
+}
+
+/// How a [`PixelPosition`] is snapped to whole pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PixelSnapMode {
+    /// The rounded position is written to the [`Transform`], so everything that reads the
+    /// [`Transform`] sees the snapped position
+    #[default]
+    Transform,
+    /// The [`Transform`] and [`GlobalTransform`] are left alone and the [`Transform`] is copied to
+    /// the [`PixelPosition`]. Only the copy of the entity's sprite that is sent to the renderer is
+    /// rounded, so the children of the entity and anything that isn't a sprite are not snapped.
+    Render,
+}
+
+/// System that switches [`PixelPosition`]s on rapier rigid bodies to [`PixelSnapMode::Render`]
+#[cfg(feature = "physics")]
+pub(crate) fn render_only_rigid_bodies(
+    mut positions: Query<
+        &mut PixelPosition,
+        With<crate::physics::bevy_rapier2d::prelude::RigidBody>,
+    >,
+) {
+    for mut position in &mut positions {
+        if position.mode != PixelSnapMode::Render {
+            position.mode = PixelSnapMode::Render;
+        }
+    }
+}
+
+/// System that writes [`PixelPosition`]s to [`Transform`]s, or copies the [`Transform`]s of
+/// entities that are only snapped when they are drawn
+pub(crate) fn snap_pixel_positions(mut positions: Query<(&mut PixelPosition, &mut Transform)>) {
+    for (mut position, mut transform) in &mut positions {
+        match position.mode {
+            PixelSnapMode::Transform => {
+                let translation = position.position.round().extend(transform.translation.z);
+                if transform.translation != translation {
+                    transform.translation = translation;
+                }
+            }
+            PixelSnapMode::Render => {
+                let translation = transform.translation.truncate();
+                if position.position != translation {
+                    position.position = translation;
+                }
+            }
+        }
+    }
+}
+
+/// System that rounds the positions of the extracted sprites of entities that are only snapped
+/// when they are drawn
+///
+/// This runs in the render app so that the [`GlobalTransform`]s in the main world keep the precise
+/// position. Rapier treats any change to the [`GlobalTransform`] of a rigid body as the body being
+/// moved by hand, so rounding it would undo the smooth simulation.
+pub(crate) fn snap_extracted_sprites(
+    mut extracted_sprites: ResMut<ExtractedSprites>,
+    positions: Extract<Query<&PixelPosition>>,
+) {
+    for sprite in &mut extracted_sprites.sprites {
+        let Ok(position) = positions.get(sprite.entity) else {
+            continue;
+        };
+        if position.mode != PixelSnapMode::Render {
+            continue;
+        }
+
+        let mut affine = sprite.transform.affine();
+        affine.translation.x = affine.translation.x.round();
+        affine.translation.y = affine.translation.y.round();
+        sprite.transform = GlobalTransform::from(affine);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        render::{ExtractSchedule, MainWorld},
+        sprite::ExtractedSprite,
+    };
+
+    use super::*;
+
+    #[test]
+    fn extracted_sprites_are_snapped() {
+        let mut main_world = MainWorld::default();
+        let transform =
+            Transform::from_xyz(10.4, -3.6, 1.5).with_rotation(Quat::from_rotation_z(0.5));
+        let render_only = main_world.spawn(PixelPosition::render_only()).id();
+        let snapped_transform = main_world.spawn(PixelPosition::default()).id();
+        let unsnapped = main_world.spawn_empty().id();
+
+        // The sprites as they are extracted by Bevy
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.insert_resource(ExtractedSprites {
+            sprites: [render_only, snapped_transform, unsnapped]
+                .into_iter()
+                .map(|entity| ExtractedSprite {
+                    entity,
+                    transform: transform.into(),
+                    color: Color::WHITE,
+                    rect: None,
+                    custom_size: None,
+                    image_handle_id: Handle::<Image>::default().id(),
+                    flip_x: false,
+                    flip_y: false,
+                    anchor: Vec2::ZERO,
+                })
+                .collect(),
+        });
+
+        let mut schedule = Schedule::new();
+        schedule.add_systems(snap_extracted_sprites);
+        render_world.add_schedule(schedule, ExtractSchedule);
+        render_world.run_schedule(ExtractSchedule);
+
+        let sprites = &render_world.resource::<ExtractedSprites>().sprites;
+        let (scale, rotation, translation) = sprites[0].transform.to_scale_rotation_translation();
+        assert_eq!(translation, Vec3::new(10.0, -4.0, 1.5));
+        assert!(rotation.abs_diff_eq(transform.rotation, 1e-6));
+        assert!(scale.abs_diff_eq(Vec3::ONE, 1e-6));
+
+        // Only the sprites of render only positions are snapped in the render app
+        for sprite in &sprites[1..] {
+            assert_eq!(sprite.transform.translation(), transform.translation);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "physics")]
+    fn rigid_bodies_simulate_smoothly() {
+        use crate::{physics::bevy_rapier2d::prelude::*, RetroPlugins};
+
+        let mut app = App::new();
+        app.add_plugins(RetroPlugins::headless())
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::ZERO,
+                timestep_mode: TimestepMode::Fixed {
+                    dt: 1.0 / 60.0,
+                    substeps: 1,
+                },
+                ..default()
+            });
+
+        // A body that is at rest between pixels, which rapier would move onto the pixel grid if its
+        // `GlobalTransform` was rounded
+        let body = app
+            .world
+            .spawn((
+                SpriteBundle {
+                    transform: Transform::from_xyz(-20.3, 0.3, 0.0),
+                    ..default()
+                },
+                PixelPosition::render_only(),
+                RigidBody::Dynamic,
+                Collider::ball(4.0),
+            ))
+            .id();
+
+        for _ in 0..10 {
+            app.update();
+        }
+
+        let context = app.world.resource::<RapierContext>();
+        let translation = context.bodies[context.entity2body()[&body]].translation();
+        let position = Vec2::new(translation.x, translation.y) * context.physics_scale();
+        assert!(
+            position.distance(Vec2::new(-20.3, 0.3)) < 1e-4,
+            "The body was moved to {}",
+            position
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Full-screen effects that are drawn to the window after the game has been scaled up to it
+
+use std::marker::PhantomData;
+
+use bevy::{
+    core_pipeline::{core_2d, fullscreen_vertex_shader::fullscreen_shader_vertex_state},
+    ecs::{query::QueryItem, system::SystemParam},
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            encase::internal::WriteInto, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
+            BufferBindingType, CachedRenderPipelineId, ColorTargetState, ColorWrites, FilterMode,
+            FragmentState, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, ShaderStages, ShaderType, TextureFormat,
+            TextureSampleType, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::BevyDefault,
+        view::ViewTarget,
+        RenderApp,
+    },
+    utils::HashMap,
+    window::PrimaryWindow,
+};
+
+use crate::{camera::RetroUpscale, RetroCamera, RetroCameraScale};
+
+/// The settings of a full-screen effect, which are added to the camera that draws to the window and
+/// sent to the effect's shader as a uniform
+///
+/// The shader is drawn with the fullscreen vertex shader, and is given the screen texture at
+/// binding `0`, a linear sampler at binding `1`, and the settings at binding `2`.
+pub(crate) trait ScreenEffect:
+    Component + ExtractComponent<Out = Self> + ShaderType + WriteInto + Clone + PartialEq
+{
+    /// The name of the effect's render graph node
+    const NAME: &'static str;
+
+    /// The effect's fragment shader
+    fn shader() -> Handle<Shader>;
+}
+
+/// The screen effect nodes in the order that they were added to the render graph
+#[derive(Resource, Default)]
+struct ScreenEffectNodes(Vec<&'static str>);
+
+/// Add the systems and render graph node for a screen effect
+///
+/// Effects are drawn in the order that they are added.
+pub(crate) fn add_screen_effect<T: ScreenEffect>(app: &mut App) {
+    app.add_plugins((
+        ExtractComponentPlugin::<T>::default(),
+        UniformComponentPlugin::<T>::default(),
+    ));
+
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .add_render_graph_node::<ViewNodeRunner<ScreenEffectNode<T>>>(core_2d::graph::NAME, T::NAME)
+        .add_render_graph_edges(
+            core_2d::graph::NAME,
+            &[
+                core_2d::graph::node::TONEMAPPING,
+                T::NAME,
+                core_2d::graph::node::END_MAIN_PASS_POST_PROCESSING,
+            ],
+        );
+
+    let mut nodes = render_app
+        .world
+        .get_resource_or_insert_with(ScreenEffectNodes::default);
+    let previous = nodes.0.last().copied();
+    nodes.0.push(T::NAME);
+    if let Some(previous) = previous {
+        render_app.add_render_graph_edge(core_2d::graph::NAME, previous, T::NAME);
+    }
+}
+
+/// Create the render pipeline for a screen effect. This must be called when the plugin that adds
+/// the effect is finished.
+pub(crate) fn init_screen_effect_pipeline<T: ScreenEffect>(app: &mut App) {
+    let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app.init_resource::<ScreenEffectPipeline<T>>();
+}
+
+/// The part of the window that a camera's view is drawn to
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScreenArea {
+    /// The camera that draws the view to the window, which is the output camera for cameras that
+    /// are scaled up to the window
+    pub camera: Entity,
+    /// The position and size of the view in physical pixels
+    pub viewport: Vec4,
+    /// The number of physical pixels along each side of a game pixel
+    pub pixel_scale: f32,
+}
+
+/// System param for finding the [`ScreenArea`]s of cameras
+#[derive(SystemParam)]
+pub(crate) struct ScreenAreas<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    output_cameras: Query<'w, 's, (Entity, &'static RetroUpscale), With<Camera>>,
+}
+
+impl<'w, 's> ScreenAreas<'w, 's> {
+    /// Get the screen area of a camera, if it is being drawn to the window
+    pub fn get(
+        &self,
+        entity: Entity,
+        camera: &Camera,
+        projection: &OrthographicProjection,
+        retro_camera: Option<&RetroCamera>,
+    ) -> Option<ScreenArea> {
+        let mode = retro_camera.map(|x| x.mode).unwrap_or_default();
+
+        match mode.resolution() {
+            // The output camera draws the game to the window, so the effect is applied by it
+            Some(resolution) => {
+                let (output, _) = self
+                    .output_cameras
+                    .iter()
+                    .find(|(_, upscale)| upscale.source == entity)?;
+                let window = self.windows.get_single().ok()?;
+                let window_size = UVec2::new(window.physical_width(), window.physical_height());
+                let scale = RetroCameraScale::new(window_size, resolution, mode.is_letterboxed());
+
+                Some(ScreenArea {
+                    camera: output,
+                    viewport: Vec4::new(
+                        scale.viewport_position.x as f32,
+                        scale.viewport_position.y as f32,
+                        scale.viewport_size.x as f32,
+                        scale.viewport_size.y as f32,
+                    ),
+                    pixel_scale: scale.scale as f32,
+                })
+            }
+            None => {
+                let (position, max) = camera.physical_viewport_rect()?;
+                let size = max - position;
+
+                Some(ScreenArea {
+                    camera: entity,
+                    viewport: Vec4::new(
+                        position.x as f32,
+                        position.y as f32,
+                        size.x as f32,
+                        size.y as f32,
+                    ),
+                    pixel_scale: size.y as f32 / projection.area.height().max(f32::EPSILON),
+                })
+            }
+        }
+    }
+}
+
+/// Insert, update, or remove the screen effect settings of cameras so that only the cameras in
+/// `new_settings` have them
+pub(crate) fn sync_screen_effect<T: ScreenEffect>(
+    commands: &mut Commands,
+    mut new_settings: HashMap<Entity, T>,
+    current_settings: &Query<(Entity, &T)>,
+) {
+    for (entity, settings) in current_settings {
+        match new_settings.remove(&entity) {
+            Some(new) if new == *settings => (),
+            Some(new) => {
+                commands.entity(entity).insert(new);
+            }
+            None => {
+                commands.entity(entity).remove::<T>();
+            }
+        }
+    }
+    for (entity, settings) in new_settings {
+        commands.entity(entity).insert(settings);
+    }
+}
+
+/// Render graph node that draws a screen effect
+struct ScreenEffectNode<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for ScreenEffectNode<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ScreenEffect> ViewNode for ScreenEffectNode<T> {
+    type ViewQuery = (&'static ViewTarget, &'static DynamicUniformIndex<T>);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, settings_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let effect_pipeline = world.resource::<ScreenEffectPipeline<T>>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let pipeline_id = if view_target.is_hdr() {
+            effect_pipeline.hdr_pipeline_id
+        } else {
+            effect_pipeline.pipeline_id
+        };
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(pipeline_id) else {
+            return Ok(());
+        };
+        let settings_uniforms = world.resource::<ComponentUniforms<T>>();
+        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        // The bind group has to be created here, because the source texture changes with every
+        // post-process write
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context
+            .render_device()
+            .create_bind_group(&BindGroupDescriptor {
+                label: Some(T::NAME),
+                layout: &effect_pipeline.layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(post_process.source),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&effect_pipeline.sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: settings_binding,
+                    },
+                ],
+            });
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some(T::NAME),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[settings_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+/// The render pipelines for a [`ScreenEffectNode`]
+#[derive(Resource)]
+struct ScreenEffectPipeline<T> {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+    hdr_pipeline_id: CachedRenderPipelineId,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: ScreenEffect> FromWorld for ScreenEffectPipeline<T> {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some(T::NAME),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(T::min_size()),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // Filter linearly so that distorted screens don't look jagged
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+
+        let pipeline_cache = world.resource_mut::<PipelineCache>();
+        let queue_pipeline = |format| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some(T::NAME.into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: T::shader(),
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            })
+        };
+        let pipeline_id = queue_pipeline(TextureFormat::bevy_default());
+        let hdr_pipeline_id = queue_pipeline(ViewTarget::TEXTURE_FORMAT_HDR);
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+            hdr_pipeline_id,
+            marker: PhantomData,
+        }
+    }
+}
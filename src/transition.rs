@@ -0,0 +1,347 @@
+//! Screen transitions
+
+use bevy::{asset::load_internal_asset, prelude::*, reflect::TypeUuid, utils::HashMap};
+
+use crate::{
+    screen_effect::{
+        add_screen_effect, init_screen_effect_pipeline, sync_screen_effect, ScreenAreas,
+        ScreenEffect,
+    },
+    RetroCamera,
+};
+
+const TRANSITION_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1789353131277187635);
+
+/// Plugin that plays screen transitions when a [`StartTransition`] event is sent
+///
+/// This is added by the [`RetroPlugins`][crate::RetroPlugins].
+pub struct RetroTransitionPlugin;
+
+impl Plugin for RetroTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            TRANSITION_SHADER_HANDLE,
+            "transition.wgsl",
+            Shader::from_wgsl
+        );
+
+        add_screen_effect::<TransitionSettings>(app);
+        app.add_event::<StartTransition>()
+            .add_event::<TransitionMidpoint>()
+            .add_event::<TransitionFinished>()
+            .init_resource::<RetroTransition>()
+            .add_systems(
+                PostUpdate,
+                (update_transition, update_transition_settings)
+                    .chain()
+                    .after(crate::camera::update_upscale),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        init_screen_effect_pipeline::<TransitionSettings>(app);
+    }
+}
+
+/// Event that starts a transition, which covers the screen of every [`RetroCamera`] and then
+/// uncovers it again
+///
+/// When the screen is fully covered a [`TransitionMidpoint`] event is sent, which is the time to
+/// change the game state or LDtk level without the player seeing it happen. Once the screen is
+/// uncovered a [`TransitionFinished`] event is sent.
+///
+/// Starting a transition while another one is playing replaces it, covering the screen from
+/// however much of it is already covered.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_retrograde::prelude::*;
+/// fn enter_door(keyboard: Res<Input<KeyCode>>, mut transitions: EventWriter<StartTransition>) {
+///     if keyboard.just_pressed(KeyCode::Space) {
+///         transitions.send(StartTransition::new(TransitionKind::Iris {
+///             center: Vec2::splat(0.5),
+///         }));
+///     }
+/// }
+///
+/// fn change_level(
+///     mut midpoints: EventReader<TransitionMidpoint>,
+///     mut level: ResMut<LevelSelection>,
+/// ) {
+///     for _ in midpoints.iter() {
+///         *level = LevelSelection::Index(1);
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct StartTransition {
+    /// How the screen is covered
+    pub kind: TransitionKind,
+    /// The color that the screen is covered with
+    ///
+    /// **Default:** [`Color::BLACK`]
+    pub color: Color,
+    /// The number of seconds it takes to cover the screen, and again to uncover it
+    ///
+    /// **Default:** `0.5`
+    pub duration: f32,
+    /// The number of seconds to keep the screen covered after the [`TransitionMidpoint`]. The
+    /// screen is always covered for at least one frame.
+    ///
+    /// **Default:** `0.0`
+    pub hold: f32,
+}
+
+impl Default for StartTransition {
+    fn default() -> Self {
+        Self {
+            kind: default(),
+            color: Color::BLACK,
+            duration: 0.5,
+            hold: 0.0,
+        }
+    }
+}
+
+impl StartTransition {
+    /// Create a transition of the given kind with the default settings
+    pub fn new(kind: TransitionKind) -> Self {
+        Self { kind, ..default() }
+    }
+}
+
+/// How a transition covers the screen
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TransitionKind {
+    /// Fade the screen to the transition color
+    #[default]
+    Fade,
+    /// Cover the screen with an edge that moves across it in the given direction, dithered on the
+    /// game's pixel grid. The screen is uncovered by the edge continuing in the same direction.
+    Wipe { direction: WipeDirection },
+    /// Close a circle around a point in the game, from `(0.0, 0.0)` at the bottom-left of the
+    /// screen to `(1.0, 1.0)` at the top-right, and then open it again
+    Iris { center: Vec2 },
+    /// Pixelate the screen into larger and larger blocks of pixels, and then dither to the
+    /// transition color
+    Pixelate,
+}
+
+/// The direction that a [`TransitionKind::Wipe`] moves in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WipeDirection {
+    #[default]
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl WipeDirection {
+    /// Get the direction as a unit vector
+    pub fn as_vec2(&self) -> Vec2 {
+        match self {
+            WipeDirection::Left => Vec2::NEG_X,
+            WipeDirection::Right => Vec2::X,
+            WipeDirection::Up => Vec2::Y,
+            WipeDirection::Down => Vec2::NEG_Y,
+        }
+    }
+}
+
+/// Event sent when a transition has fully covered the screen
+#[derive(Event, Debug, Clone)]
+pub struct TransitionMidpoint;
+
+/// Event sent when a transition has finished uncovering the screen
+#[derive(Event, Debug, Clone)]
+pub struct TransitionFinished;
+
+/// Resource with the state of the transition that is playing
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RetroTransition {
+    current: Option<ActiveTransition>,
+}
+
+#[derive(Debug, Clone)]
+struct ActiveTransition {
+    settings: StartTransition,
+    phase: TransitionPhase,
+    /// How much of the screen is covered, from `0.0` to `1.0`
+    progress: f32,
+    /// The number of seconds that the screen has been covered for
+    covered_time: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransitionPhase {
+    Covering,
+    Covered,
+    Uncovering,
+}
+
+impl RetroTransition {
+    /// Whether or not a transition is playing
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Whether or not the screen is fully covered by a transition
+    pub fn is_covered(&self) -> bool {
+        matches!(
+            self.current,
+            Some(ActiveTransition {
+                phase: TransitionPhase::Covered,
+                ..
+            })
+        )
+    }
+
+    /// How much of the screen is covered by the transition, from `0.0` to `1.0`
+    pub fn progress(&self) -> f32 {
+        self.current
+            .as_ref()
+            .map(|transition| transition.progress)
+            .unwrap_or_default()
+    }
+}
+
+/// System that starts transitions and advances the one that is playing
+fn update_transition(
+    time: Res<Time>,
+    mut transition: ResMut<RetroTransition>,
+    mut start_events: EventReader<StartTransition>,
+    mut midpoint_events: EventWriter<TransitionMidpoint>,
+    mut finished_events: EventWriter<TransitionFinished>,
+) {
+    if let Some(settings) = start_events.iter().last() {
+        let progress = transition.progress();
+        transition.current = Some(ActiveTransition {
+            settings: settings.clone(),
+            phase: TransitionPhase::Covering,
+            progress,
+            covered_time: 0.0,
+        });
+    }
+
+    let Some(current) = &mut transition.current else {
+        return;
+    };
+    let step = if current.settings.duration > 0.0 {
+        time.delta_seconds() / current.settings.duration
+    } else {
+        1.0
+    };
+
+    match current.phase {
+        TransitionPhase::Covering => {
+            current.progress = (current.progress + step).min(1.0);
+            if current.progress >= 1.0 {
+                current.phase = TransitionPhase::Covered;
+                current.covered_time = 0.0;
+                midpoint_events.send(TransitionMidpoint);
+            }
+        }
+        TransitionPhase::Covered => {
+            // Always stay covered for a frame after the midpoint, so that changes made in response
+            // to it are hidden
+            current.covered_time += time.delta_seconds();
+            if current.covered_time >= current.settings.hold {
+                current.phase = TransitionPhase::Uncovering;
+            }
+        }
+        TransitionPhase::Uncovering => {
+            current.progress = (current.progress - step).max(0.0);
+            if current.progress <= 0.0 {
+                transition.current = None;
+                finished_events.send(TransitionFinished);
+            }
+        }
+    }
+}
+
+use settings::TransitionSettings;
+
+// The `ShaderType` derive generates assertion functions that are never called, so the settings are
+// kept in their own module to allow the dead code warnings without hiding any others.
+#[allow(dead_code)]
+mod settings {
+    use bevy::{
+        prelude::*,
+        render::{extract_component::ExtractComponent, render_resource::ShaderType},
+    };
+
+    /// The transition settings that are sent to the shader, added to the cameras that draw
+    /// [`RetroCamera`][crate::RetroCamera]s to the window
+    #[derive(Component, ExtractComponent, ShaderType, Debug, Clone, Copy, PartialEq)]
+    pub struct TransitionSettings {
+        /// The position and size of the game area in physical pixels
+        pub viewport: Vec4,
+        pub color: Vec4,
+        pub center: Vec2,
+        pub direction: Vec2,
+        /// The number of physical pixels along each side of a game pixel
+        pub pixel_scale: f32,
+        pub progress: f32,
+        pub kind: u32,
+    }
+}
+
+impl ScreenEffect for TransitionSettings {
+    const NAME: &'static str = "retro_transition";
+
+    fn shader() -> Handle<Shader> {
+        TRANSITION_SHADER_HANDLE.typed()
+    }
+}
+
+/// System that adds the [`TransitionSettings`] to the cameras that draw [`RetroCamera`]s to the
+/// window while a transition is playing
+fn update_transition_settings(
+    mut commands: Commands,
+    transition: Res<RetroTransition>,
+    screen_areas: ScreenAreas,
+    cameras: Query<(Entity, &Camera, &OrthographicProjection, &RetroCamera)>,
+    current_settings: Query<(Entity, &TransitionSettings)>,
+) {
+    let mut new_settings = HashMap::default();
+    if let Some(current) = &transition.current {
+        let (kind, center, mut direction) = match current.settings.kind {
+            TransitionKind::Fade => (0, Vec2::ZERO, Vec2::ZERO),
+            TransitionKind::Wipe { direction } => (1, Vec2::ZERO, direction.as_vec2()),
+            TransitionKind::Iris { center } => (2, center, Vec2::ZERO),
+            TransitionKind::Pixelate => (3, Vec2::ZERO, Vec2::ZERO),
+        };
+        // Wipes uncover the screen from the side that they started on, so flipping the direction
+        // makes the edge look like it keeps moving the same way
+        if current.phase == TransitionPhase::Uncovering {
+            direction = -direction;
+        }
+
+        for (entity, camera, projection, retro_camera) in &cameras {
+            let Some(area) = screen_areas.get(entity, camera, projection, Some(retro_camera))
+            else {
+                continue;
+            };
+
+            new_settings.insert(
+                area.camera,
+                TransitionSettings {
+                    viewport: area.viewport,
+                    color: Vec4::from(current.settings.color.as_linear_rgba_f32()),
+                    center,
+                    direction,
+                    pixel_scale: area.pixel_scale,
+                    progress: current.progress,
+                    kind,
+                },
+            );
+        }
+    }
+
+    sync_screen_effect(&mut commands, new_settings, &current_settings);
+}
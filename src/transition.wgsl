@@ -0,0 +1,99 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader  FullscreenVertexOutput
+
+struct TransitionSettings {
+    // The game area of the screen, as the position and size in physical pixels
+    viewport: vec4<f32>,
+    // The color that the screen is covered with
+    color: vec4<f32>,
+    // The center of the iris, from (0, 0) at the bottom-left of the game to (1, 1) at the top-right
+    center: vec2<f32>,
+    // The direction that the wipe moves in
+    direction: vec2<f32>,
+    // The number of physical pixels along each side of a game pixel
+    pixel_scale: f32,
+    // How much of the screen is covered, from 0 to 1
+    progress: f32,
+    // The kind of transition: 0 is a fade, 1 is a wipe, 2 is an iris, and 3 is pixelate
+    kind: u32,
+};
+
+@group(0) @binding(0)
+var screen_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var screen_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> settings: TransitionSettings;
+
+// The width of the dithered edge of the wipe, as a fraction of the screen
+const WIPE_EDGE: f32 = 0.2;
+// The number of times that the pixelate block size doubles
+const PIXELATE_STEPS: f32 = 5.0;
+
+// Get the ordered dithering threshold of a game pixel, between 0 and 1
+fn bayer(pixel: vec2<u32>) -> f32 {
+    var matrix = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0,
+    );
+    let index = (pixel.x % 4u) + (pixel.y % 4u) * 4u;
+    return (matrix[index] + 0.5) / 16.0;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let screen_size = vec2<f32>(textureDimensions(screen_texture));
+    var color = textureSampleLevel(screen_texture, screen_sampler, in.uv, 0.0);
+
+    // Leave everything outside of the game area, such as the letterbox, alone
+    let position = in.position.xy - settings.viewport.xy;
+    if any(position < vec2<f32>(0.0)) || any(position > settings.viewport.zw) {
+        return color;
+    }
+
+    // Work in game pixels so that the transition is drawn on the game's pixel grid
+    let pixel_scale = max(settings.pixel_scale, 1.0);
+    let pixel = floor(position / pixel_scale);
+    let game_size = settings.viewport.zw / pixel_scale;
+    // The center of the game pixel, from (0, 0) at the bottom-left of the game to (1, 1) at the
+    // top-right
+    let uv = vec2<f32>(pixel.x + 0.5, game_size.y - pixel.y - 0.5) / game_size;
+    let threshold = bayer(vec2<u32>(pixel));
+
+    var cover = 0.0;
+    switch settings.kind {
+        // Wipe
+        case 1u: {
+            // Sweep a dithered edge across the screen from the side that the wipe starts on
+            let direction = settings.direction;
+            let distance = dot(uv - 0.5, direction) / (abs(direction.x) + abs(direction.y)) + 0.5;
+            let edge = settings.progress * (1.0 + WIPE_EDGE);
+            cover = step(threshold, (edge - distance) / WIPE_EDGE);
+        }
+        // Iris
+        case 2u: {
+            // Shrink a circle around the center until it closes
+            let offset = (uv - settings.center) * game_size;
+            let corners = max(settings.center, 1.0 - settings.center) * game_size;
+            let radius = (1.0 - settings.progress) * (length(corners) + 1.0);
+            cover = step(radius, length(offset));
+        }
+        // Pixelate
+        case 3u: {
+            // Sample the middle pixel of blocks of pixels that double in size, and then dither to
+            // the cover color
+            let block = exp2(floor(settings.progress * PIXELATE_STEPS));
+            let block_center = (floor(pixel / block) * block + floor(block / 2.0) + 0.5) * pixel_scale;
+            let sample_uv = (settings.viewport.xy + min(block_center, settings.viewport.zw)) / screen_size;
+            color = textureSampleLevel(screen_texture, screen_sampler, sample_uv, 0.0);
+            cover = step(threshold, (settings.progress - 0.6) / 0.4);
+        }
+        // Fade
+        default: {
+            cover = settings.progress;
+        }
+    }
+
+    return vec4<f32>(mix(color.rgb, settings.color.rgb, cover * settings.color.a), color.a);
+}